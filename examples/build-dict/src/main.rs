@@ -0,0 +1,59 @@
+//! Build a `load_dict` compatible dictionary from a segmented corpus.
+//!
+//! ```bash
+//! cargo run --release -p build-dict -- corpus.utf8 > dict.txt
+//! cargo run --release -p build-dict -- --merge --scale 0.5 corpus.utf8 > user_dict.txt
+//! ```
+//!
+//! Each corpus line contains whitespace-separated words, optionally tagged as `word/tag`.
+//! With `--merge`, the counts are merged into the embedded dictionary; loading the output
+//! on top of it yields the merged dictionary.
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::process;
+
+use jieba_rs::{DictBuilder, Jieba};
+
+fn usage() -> ! {
+    eprintln!("Usage: build-dict [--merge] [--scale FACTOR] CORPUS...");
+    process::exit(2);
+}
+
+fn main() -> Result<(), jieba_rs::Error> {
+    let mut merge = false;
+    let mut scale = None;
+    let mut corpora = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--merge" => merge = true,
+            "--scale" => {
+                let value = args.next().unwrap_or_else(|| usage());
+                scale = Some(value.parse::<f64>().unwrap_or_else(|_| usage()));
+            }
+            "-h" | "--help" => usage(),
+            _ => corpora.push(arg),
+        }
+    }
+    if corpora.is_empty() {
+        usage();
+    }
+
+    let mut builder = DictBuilder::new();
+    for path in &corpora {
+        let mut reader = BufReader::new(File::open(path)?);
+        builder.add_reader(&mut reader)?;
+    }
+
+    let entries = if merge {
+        builder.merged_entries(&Jieba::new(), scale)
+    } else {
+        builder.entries()
+    };
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    DictBuilder::write_dict(&entries, &mut out)?;
+    Ok(())
+}
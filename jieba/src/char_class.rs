@@ -0,0 +1,164 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{is_cjk, is_han_cut_all, is_han_default, is_skip_cut_all};
+
+type Classifier = Arc<dyn Fn(char) -> bool + Send + Sync>;
+
+/// Character classes deciding how the input text is split into blocks before segmentation
+///
+/// Runs of "Han" characters are segmented with the dictionary, anything else is emitted
+/// char by char. Each class can be replaced to segment more characters, such as the
+/// middle dot of `迈克尔·乔丹`, kana or apostrophes. Classes left unset keep the
+/// behavior of Python jieba's `re_han_default`, `re_han_cut_all` and `re_skip_cut_all`.
+/// Set them on a [`Jieba`](crate::Jieba) instance with
+/// [`Jieba::set_char_classes`](crate::Jieba::set_char_classes).
+///
+/// # Examples
+///
+/// ```
+/// use jieba_rs::CharClasses;
+///
+/// let classes = CharClasses::new().han_default(|c| CharClasses::default_han_default(c) || c == '·');
+/// assert!(classes.is_han_default('·'));
+/// assert!(classes.is_han_default('中'));
+/// assert!(!classes.is_han_cut_all('·'));
+/// ```
+#[derive(Clone, Default)]
+pub struct CharClasses {
+    cjk: Option<Classifier>,
+    han_default: Option<Classifier>,
+    han_cut_all: Option<Classifier>,
+    skip_cut_all: Option<Classifier>,
+}
+
+impl fmt::Debug for CharClasses {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let custom = |classifier: &Option<Classifier>| if classifier.is_some() { "custom" } else { "default" };
+        f.debug_struct("CharClasses")
+            .field("cjk", &custom(&self.cjk))
+            .field("han_default", &custom(&self.han_default))
+            .field("han_cut_all", &custom(&self.han_cut_all))
+            .field("skip_cut_all", &custom(&self.skip_cut_all))
+            .finish()
+    }
+}
+
+impl CharClasses {
+    /// Creates the default character classes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the CJK ideograph class, used by the default Han classes and to decide which
+    /// words the POS tagging model can tag.
+    pub fn cjk<F: Fn(char) -> bool + Send + Sync + 'static>(mut self, classify: F) -> Self {
+        self.cjk = Some(Arc::new(classify));
+        self
+    }
+
+    /// Sets the class of characters segmented together in the default and search modes,
+    /// CJK ideographs, ASCII letters and digits and `+#&._%-` by default.
+    pub fn han_default<F: Fn(char) -> bool + Send + Sync + 'static>(mut self, classify: F) -> Self {
+        self.han_default = Some(Arc::new(classify));
+        self
+    }
+
+    /// Sets the class of characters segmented together in full mode, CJK ideographs by default.
+    pub fn han_cut_all<F: Fn(char) -> bool + Send + Sync + 'static>(mut self, classify: F) -> Self {
+        self.han_cut_all = Some(Arc::new(classify));
+        self
+    }
+
+    /// Sets the class of characters emitted one at a time in full mode, outside of Han
+    /// blocks. Anything but ASCII letters and digits, `+`, `#` and newlines by default,
+    /// the other characters being emitted as runs.
+    pub fn skip_cut_all<F: Fn(char) -> bool + Send + Sync + 'static>(mut self, classify: F) -> Self {
+        self.skip_cut_all = Some(Arc::new(classify));
+        self
+    }
+
+    /// Whether the character is a CJK ideograph
+    #[inline]
+    pub fn is_cjk(&self, c: char) -> bool {
+        match &self.cjk {
+            Some(classify) => classify(c),
+            None => is_cjk(c),
+        }
+    }
+
+    /// Whether the character is segmented in the default and search modes
+    #[inline]
+    pub fn is_han_default(&self, c: char) -> bool {
+        match (&self.han_default, &self.cjk) {
+            (Some(classify), _) => classify(c),
+            (None, None) => is_han_default(c),
+            (None, Some(_)) => self.is_cjk(c) || is_han_default(c),
+        }
+    }
+
+    /// Whether the character is segmented in full mode
+    #[inline]
+    pub fn is_han_cut_all(&self, c: char) -> bool {
+        match &self.han_cut_all {
+            Some(classify) => classify(c),
+            None => self.is_cjk(c),
+        }
+    }
+
+    /// Whether the character is emitted on its own in full mode
+    #[inline]
+    pub fn is_skip_cut_all(&self, c: char) -> bool {
+        match &self.skip_cut_all {
+            Some(classify) => classify(c),
+            None => is_skip_cut_all(c),
+        }
+    }
+
+    /// The default CJK ideograph class
+    pub fn default_cjk(c: char) -> bool {
+        is_cjk(c)
+    }
+
+    /// The default class of characters segmented in the default and search modes
+    pub fn default_han_default(c: char) -> bool {
+        is_han_default(c)
+    }
+
+    /// The default class of characters segmented in full mode
+    pub fn default_han_cut_all(c: char) -> bool {
+        is_han_cut_all(c)
+    }
+
+    /// The default class of characters emitted on their own in full mode
+    pub fn default_skip_cut_all(c: char) -> bool {
+        is_skip_cut_all(c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_char_classes() {
+        let classes = CharClasses::new();
+        for c in ['中', 'a', '1', '+', '#', '&', '.', '_', '%', '-', '·', 'の', ' ', '\n'] {
+            assert_eq!(classes.is_cjk(c), is_cjk(c), "{c:?}");
+            assert_eq!(classes.is_han_default(c), is_han_default(c), "{c:?}");
+            assert_eq!(classes.is_han_cut_all(c), is_han_cut_all(c), "{c:?}");
+            assert_eq!(classes.is_skip_cut_all(c), is_skip_cut_all(c), "{c:?}");
+        }
+    }
+
+    #[test]
+    fn test_custom_cjk() {
+        let kana = |c: char| matches!(c, '\u{3040}'..='\u{30FF}');
+        let classes = CharClasses::new().cjk(move |c| CharClasses::default_cjk(c) || kana(c));
+        assert!(classes.is_cjk('の'));
+        assert!(classes.is_han_default('の'));
+        assert!(classes.is_han_default('a'));
+        assert!(classes.is_han_cut_all('の'));
+        assert!(!classes.is_han_cut_all('a'));
+    }
+}
@@ -0,0 +1,348 @@
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::FxHashMap;
+use crate::Jieba;
+use crate::errors::Error;
+
+/// Number of lines counted per batch by [`DictBuilder::add_reader`]
+const BATCH_SIZE: usize = 10_000;
+
+/// A dictionary entry in the [`Jieba::load_dict`] format
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DictEntry {
+    /// Word
+    pub word: String,
+    /// Word frequency
+    pub freq: usize,
+    /// Word tag, may be empty
+    pub tag: String,
+}
+
+impl fmt::Display for DictEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Words with whitespace are quoted for `Jieba::load_dict`
+        if self.word.contains(char::is_whitespace) || self.word.starts_with('"') {
+            write!(f, "\"{}\"", self.word.replace('\\', "\\\\").replace('"', "\\\""))?;
+        } else {
+            write!(f, "{}", self.word)?;
+        }
+        if self.tag.is_empty() {
+            write!(f, " {}", self.freq)
+        } else {
+            write!(f, " {} {}", self.freq, self.tag)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct WordCount {
+    freq: usize,
+    tags: FxHashMap<String, usize>,
+}
+
+impl WordCount {
+    /// Most frequent tag of the word, ties broken by the tag itself
+    fn best_tag(&self) -> &str {
+        self.tags
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map_or("", |(tag, _)| tag.as_str())
+    }
+}
+
+/// Splits a corpus token into its word and optional tag.
+///
+/// Accepts `word` and `word/tag`, as well as the bracketed compounds of the
+/// People's Daily corpus such as `[中国/ns` and `政府/n]nt`.
+pub(crate) fn parse_token(token: &str) -> Option<(&str, &str)> {
+    let token = match token.strip_prefix('[') {
+        Some(rest) if !rest.is_empty() => rest,
+        _ => token,
+    };
+    if let Some((word, tag)) = token.rsplit_once('/') {
+        let tag = tag.split(']').next().unwrap_or("");
+        if !word.is_empty() && !tag.is_empty() && tag.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Some((word, tag));
+        }
+    }
+    if token.is_empty() { None } else { Some((token, "")) }
+}
+
+/// Builds a word frequency dictionary from a segmented corpus
+///
+/// Each corpus line contains words separated by whitespace, optionally
+/// tagged as `word/tag`.
+///
+/// # Examples
+///
+/// ```
+/// use jieba_rs::{DictBuilder, Jieba};
+///
+/// let mut builder = DictBuilder::new();
+/// builder.add_line("我/r 爱/v 区块链/n");
+/// builder.add_line("区块链/n 很/d 火/a");
+/// assert_eq!(builder.word_freq("区块链"), 2);
+///
+/// let mut dict = Vec::new();
+/// DictBuilder::write_dict(&builder.entries(), &mut dict).unwrap();
+/// let jieba = Jieba::with_dict(&mut dict.as_slice()).unwrap();
+/// assert!(jieba.has_word("区块链"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DictBuilder {
+    words: FxHashMap<String, WordCount>,
+    tags: FxHashMap<String, usize>,
+    total: usize,
+}
+
+impl DictBuilder {
+    /// Creates an empty DictBuilder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts the words of a segmented line
+    pub fn add_line(&mut self, line: &str) {
+        for (word, tag) in line.split_whitespace().filter_map(parse_token) {
+            let count = match self.words.get_mut(word) {
+                Some(count) => count,
+                None => self.words.entry(word.to_string()).or_default(),
+            };
+            count.freq += 1;
+            self.total += 1;
+            if !tag.is_empty() {
+                match count.tags.get_mut(tag) {
+                    Some(freq) => *freq += 1,
+                    None => {
+                        count.tags.insert(tag.to_string(), 1);
+                    }
+                }
+                match self.tags.get_mut(tag) {
+                    Some(freq) => *freq += 1,
+                    None => {
+                        self.tags.insert(tag.to_string(), 1);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Counts the words of many segmented lines.
+    ///
+    /// Lines are counted in parallel when the `parallel` feature is enabled.
+    pub fn add_lines<S: AsRef<str> + Sync>(&mut self, lines: &[S]) {
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+
+            let counted = lines
+                .par_iter()
+                .fold(DictBuilder::new, |mut builder, line| {
+                    builder.add_line(line.as_ref());
+                    builder
+                })
+                .reduce(DictBuilder::new, |mut a, b| {
+                    a.merge(b);
+                    a
+                });
+            self.merge(counted);
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        for line in lines {
+            self.add_line(line.as_ref());
+        }
+    }
+
+    /// Counts the words of a segmented corpus, one sentence per line
+    pub fn add_reader<R: BufRead>(&mut self, reader: &mut R) -> Result<(), Error> {
+        let mut lines = Vec::with_capacity(BATCH_SIZE);
+        for line in reader.lines() {
+            lines.push(line?);
+            if lines.len() == BATCH_SIZE {
+                self.add_lines(&lines);
+                lines.clear();
+            }
+        }
+        self.add_lines(&lines);
+        Ok(())
+    }
+
+    /// Adds the counts of `other` to this builder
+    pub fn merge(&mut self, other: DictBuilder) {
+        for (word, count) in other.words {
+            let entry = self.words.entry(word).or_default();
+            entry.freq += count.freq;
+            for (tag, freq) in count.tags {
+                *entry.tags.entry(tag).or_insert(0) += freq;
+            }
+        }
+        for (tag, freq) in other.tags {
+            *self.tags.entry(tag).or_insert(0) += freq;
+        }
+        self.total += other.total;
+    }
+
+    /// Number of times `word` was seen
+    pub fn word_freq(&self, word: &str) -> usize {
+        self.words.get(word).map_or(0, |c| c.freq)
+    }
+
+    /// Number of times `tag` was seen
+    pub fn tag_freq(&self, tag: &str) -> usize {
+        self.tags.get(tag).copied().unwrap_or(0)
+    }
+
+    /// All seen tags with their frequencies, ordered by tag
+    pub fn tag_freqs(&self) -> Vec<(&str, usize)> {
+        let mut tags: Vec<(&str, usize)> = self.tags.iter().map(|(t, &f)| (t.as_str(), f)).collect();
+        tags.sort_unstable();
+        tags
+    }
+
+    /// Total number of counted words
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Number of distinct words
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns `true` if no word has been counted
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Dictionary entries with the raw counts, each word tagged with its most frequent tag.
+    ///
+    /// Entries are ordered by word.
+    pub fn entries(&self) -> Vec<DictEntry> {
+        let mut entries: Vec<DictEntry> = self
+            .words
+            .iter()
+            .map(|(word, count)| DictEntry {
+                word: word.clone(),
+                freq: count.freq,
+                tag: count.best_tag().to_string(),
+            })
+            .collect();
+        entries.sort_unstable_by(|a, b| a.word.cmp(&b.word));
+        entries
+    }
+
+    /// Dictionary entries merged with the dictionary of `jieba`.
+    ///
+    /// Counts are multiplied by `scale` and added to the existing frequencies. If `scale`
+    /// is `None`, it is `jieba.total / self.total`, so the corpus weighs as much as the
+    /// existing dictionary. Entries of words already in the dictionary keep their tag unless
+    /// it is empty, in which case they get the most frequent tag of the corpus.
+    ///
+    /// Loading the returned entries with [`Jieba::load_dict`] on top of the existing
+    /// dictionary updates the frequencies and adds the new words, but [`Jieba::load_dict`]
+    /// never changes the tag of an existing word, so words with an empty tag stay untagged.
+    /// To apply these tags too, load the entries into a [`Jieba`] without those words.
+    pub fn merged_entries(&self, jieba: &Jieba, scale: Option<f64>) -> Vec<DictEntry> {
+        let scale = scale.unwrap_or_else(|| {
+            if self.total == 0 {
+                1.0
+            } else {
+                jieba.total as f64 / self.total as f64
+            }
+        });
+        let mut entries = self.entries();
+        for entry in &mut entries {
+            let scaled = ((entry.freq as f64 * scale).round() as usize).max(1);
+            match jieba.word_id(&entry.word) {
+                Some(word_id) => {
+                    let record = &jieba.records[word_id as usize];
+                    entry.freq = record.freq + scaled;
+                    if !record.tag.is_empty() {
+                        entry.tag = record.tag.to_string();
+                    }
+                }
+                None => entry.freq = scaled,
+            }
+        }
+        entries
+    }
+
+    /// Writes `entries` as lines that [`Jieba::load_dict`] accepts.
+    pub fn write_dict<W: Write>(entries: &[DictEntry], writer: &mut W) -> io::Result<()> {
+        for entry in entries {
+            writeln!(writer, "{entry}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_token() {
+        assert_eq!(parse_token("中国/ns"), Some(("中国", "ns")));
+        assert_eq!(parse_token("中国"), Some(("中国", "")));
+        assert_eq!(parse_token("[中国/ns"), Some(("中国", "ns")));
+        assert_eq!(parse_token("政府/n]nt"), Some(("政府", "n")));
+        assert_eq!(parse_token("1/2"), Some(("1/2", "")));
+        assert_eq!(parse_token("/"), Some(("/", "")));
+    }
+
+    #[test]
+    fn test_build_dict() {
+        let corpus = "我/r 爱/v 北京/ns\n北京/ns 欢迎/v 你/r\n北京/n\n";
+        let mut builder = DictBuilder::new();
+        builder.add_reader(&mut corpus.as_bytes()).unwrap();
+
+        assert_eq!(builder.total(), 7);
+        assert_eq!(builder.word_freq("北京"), 3);
+        assert_eq!(builder.tag_freq("r"), 2);
+        assert_eq!(builder.tag_freqs(), vec![("n", 1), ("ns", 2), ("r", 2), ("v", 2)]);
+
+        let mut dict = Vec::new();
+        DictBuilder::write_dict(&builder.entries(), &mut dict).unwrap();
+        assert_eq!(
+            String::from_utf8(dict).unwrap(),
+            "你 1 r\n北京 3 ns\n我 1 r\n欢迎 1 v\n爱 1 v\n"
+        );
+    }
+
+    #[test]
+    fn test_merged_entries() {
+        let mut jieba = Jieba::empty();
+        jieba.add_word("北京", Some(90), Some("ns"));
+        jieba.add_word("天安门", Some(10), None);
+
+        let mut builder = DictBuilder::new();
+        builder.add_lines(&["北京/n 天安门/ns", "北京 欢迎"]);
+
+        let entries = builder.merged_entries(&jieba, None);
+        let expected = vec![
+            DictEntry {
+                word: "北京".to_string(),
+                freq: 140,
+                tag: "ns".to_string(),
+            },
+            DictEntry {
+                word: "天安门".to_string(),
+                freq: 35,
+                tag: "ns".to_string(),
+            },
+            DictEntry {
+                word: "欢迎".to_string(),
+                freq: 25,
+                tag: String::new(),
+            },
+        ];
+        assert_eq!(entries, expected);
+
+        let mut dict = Vec::new();
+        DictBuilder::write_dict(&entries, &mut dict).unwrap();
+        jieba.load_dict(&mut dict.as_slice()).unwrap();
+        assert!(jieba.has_word("欢迎"));
+    }
+}
@@ -0,0 +1,359 @@
+use std::cmp::Reverse;
+use std::ops::Range;
+
+use crate::{Jieba, Sentence, SentenceSplitter, Tag, Token, TokenizeMode};
+
+/// Options of [`Jieba::document`]
+///
+/// By default, the text is cut in [`TokenizeMode::Default`] with HMM, tagged and split
+/// into sentences with the default [`SentenceSplitter`].
+#[derive(Debug, Clone, Copy)]
+pub struct DocumentConfig {
+    mode: TokenizeMode,
+    hmm: bool,
+    tag: bool,
+    splitter: SentenceSplitter,
+}
+
+impl Default for DocumentConfig {
+    fn default() -> Self {
+        DocumentConfig {
+            mode: TokenizeMode::Default,
+            hmm: true,
+            tag: true,
+            splitter: SentenceSplitter::default(),
+        }
+    }
+}
+
+impl DocumentConfig {
+    /// Creates the default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the segmentation mode
+    pub fn mode(mut self, mode: TokenizeMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Enables HMM for out-of-vocabulary words
+    pub fn hmm(mut self, enabled: bool) -> Self {
+        self.hmm = enabled;
+        self
+    }
+
+    /// Enables POS tagging
+    pub fn tag(mut self, enabled: bool) -> Self {
+        self.tag = enabled;
+        self
+    }
+
+    /// Sets the sentence splitter
+    pub fn splitter(mut self, splitter: SentenceSplitter) -> Self {
+        self.splitter = splitter;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct DocumentToken {
+    start: usize,
+    end: usize,
+    byte_start: usize,
+    byte_end: usize,
+    tag: Box<str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct DocumentSentence {
+    start: usize,
+    end: usize,
+    byte_start: usize,
+    byte_end: usize,
+    tokens: Range<usize>,
+}
+
+/// A segmented text, owning its text, tokens, tags and sentences
+///
+/// Built in one pass by [`Jieba::document`]. Tokens are ordered by their start, then from
+/// the longest, and grouped into sentences as in [`Sentence::tokens`]. In search mode,
+/// tokens overlap. Tokens, tags and sentences are borrowed from the document as the
+/// [`Token`], [`Tag`] and [`Sentence`] returned by [`Jieba`], with the same offsets.
+///
+/// Keyword extractors accept a document with
+/// [`KeywordExtract::extract_keywords_from_document`](crate::KeywordExtract::extract_keywords_from_document).
+///
+/// # Examples
+///
+/// ```
+/// use jieba_rs::{DocumentConfig, Jieba};
+///
+/// let mut jieba = Jieba::empty();
+/// jieba.add_word("天气", Some(100), Some("n"));
+/// jieba.add_word("很好", Some(100), Some("a"));
+/// let document = jieba.document("天气很好。 走吧！", DocumentConfig::new());
+///
+/// assert_eq!(document.sentence_count(), 2);
+/// let words: Vec<&str> = document.sentence_tokens(0).map(|t| t.word).collect();
+/// assert_eq!(words, vec!["天气", "很好", "。"]);
+/// assert_eq!(document.tag(1).tag, "a");
+///
+/// let token = document.token_at_char(3).unwrap();
+/// assert_eq!(document.token(token).word, "很好");
+/// assert_eq!(document.sentence_at_byte(document.text().find('走').unwrap()), Some(1));
+/// assert_eq!(document.sentence_at_char(5), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Document {
+    text: String,
+    tagged: bool,
+    tokens: Vec<DocumentToken>,
+    sentences: Vec<DocumentSentence>,
+}
+
+impl Document {
+    pub(crate) fn new(jieba: &Jieba, text: &str, config: DocumentConfig) -> Self {
+        let mut tokens: Vec<DocumentToken> = if config.tag {
+            let tags = match config.mode {
+                TokenizeMode::Default => jieba.tag(text, config.hmm),
+                TokenizeMode::Search => jieba.tag_for_search(text, config.hmm),
+            };
+            tags.into_iter()
+                .map(|tag| DocumentToken {
+                    start: tag.start,
+                    end: tag.end,
+                    byte_start: tag.byte_start,
+                    byte_end: tag.byte_end,
+                    tag: tag.tag.into(),
+                })
+                .collect()
+        } else {
+            jieba
+                .tokenize(text, config.mode, config.hmm)
+                .into_iter()
+                .map(|token| DocumentToken {
+                    start: token.start,
+                    end: token.end,
+                    byte_start: token.byte_start,
+                    byte_end: token.byte_end,
+                    tag: "".into(),
+                })
+                .collect()
+        };
+        tokens.sort_by_key(|token| (token.byte_start, Reverse(token.byte_end)));
+
+        let sentences = config
+            .splitter
+            .split(text)
+            .into_iter()
+            .map(|sentence| {
+                let first = tokens.partition_point(|token| token.byte_start < sentence.byte_start);
+                let last = tokens.partition_point(|token| token.byte_start < sentence.byte_end);
+                DocumentSentence {
+                    start: sentence.start,
+                    end: sentence.end,
+                    byte_start: sentence.byte_start,
+                    byte_end: sentence.byte_end,
+                    tokens: first..last.max(first),
+                }
+            })
+            .collect();
+
+        Document {
+            text: text.to_string(),
+            tagged: config.tag,
+            tokens,
+            sentences,
+        }
+    }
+
+    /// The text of the document
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Whether the tokens are tagged, otherwise their tags are empty
+    pub fn is_tagged(&self) -> bool {
+        self.tagged
+    }
+
+    /// Number of tokens
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Whether the document has no tokens
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Token at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn token(&self, index: usize) -> Token<'_> {
+        let token = &self.tokens[index];
+        Token {
+            word: &self.text[token.byte_start..token.byte_end],
+            start: token.start,
+            end: token.end,
+            byte_start: token.byte_start,
+            byte_end: token.byte_end,
+        }
+    }
+
+    /// Tagged token at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn tag(&self, index: usize) -> Tag<'_> {
+        let token = &self.tokens[index];
+        Tag {
+            word: &self.text[token.byte_start..token.byte_end],
+            tag: &token.tag,
+            start: token.start,
+            end: token.end,
+            byte_start: token.byte_start,
+            byte_end: token.byte_end,
+        }
+    }
+
+    /// All the tokens
+    pub fn tokens(&self) -> impl ExactSizeIterator<Item = Token<'_>> + '_ {
+        (0..self.tokens.len()).map(|index| self.token(index))
+    }
+
+    /// All the tagged tokens
+    pub fn tags(&self) -> impl ExactSizeIterator<Item = Tag<'_>> + '_ {
+        (0..self.tokens.len()).map(|index| self.tag(index))
+    }
+
+    /// Number of sentences
+    pub fn sentence_count(&self) -> usize {
+        self.sentences.len()
+    }
+
+    /// Sentence at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn sentence(&self, index: usize) -> Sentence<'_> {
+        let sentence = &self.sentences[index];
+        Sentence {
+            text: &self.text[sentence.byte_start..sentence.byte_end],
+            start: sentence.start,
+            end: sentence.end,
+            byte_start: sentence.byte_start,
+            byte_end: sentence.byte_end,
+        }
+    }
+
+    /// All the sentences
+    pub fn sentences(&self) -> impl ExactSizeIterator<Item = Sentence<'_>> + '_ {
+        (0..self.sentences.len()).map(|index| self.sentence(index))
+    }
+
+    /// Indices of the tokens of the sentence at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn sentence_token_range(&self, index: usize) -> Range<usize> {
+        self.sentences[index].tokens.clone()
+    }
+
+    /// Tokens of the sentence at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn sentence_tokens(&self, index: usize) -> impl ExactSizeIterator<Item = Token<'_>> + '_ {
+        self.sentence_token_range(index).map(|index| self.token(index))
+    }
+
+    /// Index of the token containing the byte at `byte`, the innermost one in search mode
+    pub fn token_at_byte(&self, byte: usize) -> Option<usize> {
+        let after = self.tokens.partition_point(|token| token.byte_start <= byte);
+        (0..after).rev().find(|&index| self.tokens[index].byte_end > byte)
+    }
+
+    /// Index of the token containing the char at `char`, the innermost one in search mode
+    pub fn token_at_char(&self, char: usize) -> Option<usize> {
+        let after = self.tokens.partition_point(|token| token.start <= char);
+        (0..after).rev().find(|&index| self.tokens[index].end > char)
+    }
+
+    /// Index of the sentence containing the byte at `byte`, `None` between sentences
+    pub fn sentence_at_byte(&self, byte: usize) -> Option<usize> {
+        let index = self
+            .sentences
+            .partition_point(|sentence| sentence.byte_start <= byte)
+            .checked_sub(1)?;
+        (self.sentences[index].byte_end > byte).then_some(index)
+    }
+
+    /// Index of the sentence containing the char at `char`, `None` between sentences
+    pub fn sentence_at_char(&self, char: usize) -> Option<usize> {
+        let index = self
+            .sentences
+            .partition_point(|sentence| sentence.start <= char)
+            .checked_sub(1)?;
+        (self.sentences[index].end > char).then_some(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_mode_document() {
+        let mut jieba = Jieba::empty();
+        jieba.add_word("中华", Some(100), Some("nz"));
+        jieba.add_word("华人", Some(100), Some("n"));
+        jieba.add_word("中华人民", Some(1000), Some("nt"));
+        let config = DocumentConfig::new().mode(TokenizeMode::Search).hmm(false);
+        let document = jieba.document("中华人民！", config);
+
+        let words: Vec<(&str, &str)> = document.tags().map(|t| (t.word, t.tag)).collect();
+        assert_eq!(
+            words,
+            vec![("中华人民", "nt"), ("中华", "nz"), ("华人", "n"), ("！", "x")]
+        );
+        assert_eq!(document.token_at_char(0), Some(1));
+        assert_eq!(document.token_at_char(2), Some(2));
+        assert_eq!(document.token_at_char(3), Some(0));
+        assert_eq!(document.token_at_byte(document.text().len()), None);
+        assert_eq!(document.sentence_token_range(0), 0..4);
+    }
+
+    #[test]
+    fn test_search_mode_document_normalized() {
+        let mut jieba = Jieba::empty();
+        jieba.add_word("中华", Some(100), Some("nz"));
+        jieba.add_word("中华人民", Some(1000), Some("nt"));
+        let mut normalizer = crate::Normalizer::new();
+        normalizer.add_conversion("華", "华");
+        jieba.set_normalizer(normalizer);
+        let config = DocumentConfig::new().mode(TokenizeMode::Search).hmm(false);
+        let document = jieba.document("中華人民", config);
+
+        let words: Vec<(&str, &str)> = document.tags().map(|t| (t.word, t.tag)).collect();
+        assert_eq!(words, vec![("中華人民", "nt"), ("中華", "nz")]);
+    }
+
+    #[test]
+    fn test_untagged_document() {
+        let jieba = Jieba::empty();
+        let document = jieba.document("  ", DocumentConfig::new().tag(false));
+        assert!(!document.is_tagged());
+        assert_eq!(document.sentence_count(), 0);
+        assert_eq!(document.sentence_at_byte(0), None);
+        assert!(document.tags().all(|t| t.tag.is_empty()));
+    }
+}
@@ -0,0 +1,385 @@
+use crate::{PosCategory, PosTag, Tag, Upos};
+
+/// Kind of a named entity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntityKind {
+    /// Person name, from the `nr`, `nrfg` and `nrt` tags
+    Person,
+    /// Place name, from the `ns` tag
+    Place,
+    /// Organization name, from the `nt` tag
+    Organization,
+    /// Time expression, from the `t` tag
+    Time,
+}
+
+/// A named entity span
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Entity<'a> {
+    /// Text of the entity
+    pub text: &'a str,
+    /// Kind of the entity
+    pub kind: EntityKind,
+    /// Unicode start position of the entity in the original input
+    pub start: usize,
+    /// Unicode end position of the entity in the original input
+    pub end: usize,
+    /// Byte start position of the entity in the original input
+    pub byte_start: usize,
+    /// Byte end position of the entity in the original input
+    pub byte_end: usize,
+}
+
+/// Compound surnames, single character surnames are in [`SINGLE_SURNAMES`]
+const COMPOUND_SURNAMES: &[&str] = &[
+    "欧阳", "司马", "上官", "诸葛", "东方", "皇甫", "尉迟", "公孙", "慕容", "长孙", "宇文", "司徒", "夏侯", "轩辕",
+    "令狐", "端木", "独孤", "南宫", "西门", "申屠",
+];
+
+const SINGLE_SURNAMES: &str = "王李张刘陈杨黄赵吴周徐孙马朱胡郭何高林罗郑梁谢宋唐许韩冯邓曹彭曾肖田董袁潘于蒋蔡余杜叶程苏魏吕丁任沈姚卢姜崔钟谭陆汪范金石廖贾夏韦付方白邹孟熊秦邱江尹薛闫段雷侯龙史陶黎贺顾毛郝龚邵万钱严覃武戴莫孔向汤常温康施文牛樊葛邢安齐易乔伍庞颜倪庄聂章鲁岳翟殷詹申欧耿关兰焦俞左柳甘祝包宁尚符舒阮柯纪梅童凌毕单季裴霍涂成苗谷盛曲翁冉骆蓝路游辛靳管柴蒙鲍华喻祁蒲房滕屈饶解牟艾尤阳时穆农司卓古吉缪简车项连芦麦褚娄窦戚岑景党宫费卜冷晏席卫米柏宗瞿桂全佟应臧闵苟邬边卞姬师和仇栾隋商刁沙荣巫寇桑郎甄丛仲虞敖巩明佘池查麻苑迟邝";
+
+/// Administrative suffixes merged into a preceding place name
+const PLACE_SUFFIXES: &[&str] = &[
+    "省",
+    "市",
+    "县",
+    "区",
+    "镇",
+    "乡",
+    "村",
+    "州",
+    "盟",
+    "旗",
+    "自治区",
+    "自治州",
+    "自治县",
+    "特别行政区",
+    "街道",
+];
+
+/// Suffixes that end an organization name
+const ORGANIZATION_SUFFIXES: &[&str] = &[
+    "公司",
+    "有限公司",
+    "股份有限公司",
+    "集团",
+    "大学",
+    "学院",
+    "中学",
+    "小学",
+    "学校",
+    "银行",
+    "医院",
+    "研究所",
+    "研究院",
+    "科学院",
+    "委员会",
+    "协会",
+    "学会",
+    "基金会",
+    "法院",
+    "检察院",
+    "出版社",
+    "报社",
+    "电视台",
+    "酒店",
+    "大酒店",
+    "饭店",
+    "政府",
+    "部",
+    "局",
+    "厅",
+    "署",
+];
+
+/// Time units following a numeral
+const TIME_UNITS: &str = "年月日号时点分秒";
+
+/// Maximum number of tokens of an organization name
+const MAX_ORGANIZATION_TOKENS: usize = 6;
+
+fn is_surname(word: &str) -> bool {
+    let mut chars = word.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => SINGLE_SURNAMES.contains(c),
+        _ => COMPOUND_SURNAMES.contains(&word),
+    }
+}
+
+/// Whether a token can be the surname of a person name: a surname tagged as a person
+/// name or left untagged, so that 和 in 我和张三 tagged as a conjunction is not one
+fn is_surname_token(tag: &Tag<'_>) -> bool {
+    is_surname(tag.word) && (tag.tag.starts_with("nr") || tag.tag == "x" || tag.tag.is_empty())
+}
+
+fn is_single_han(word: &str) -> bool {
+    let mut chars = word.chars();
+    matches!((chars.next(), chars.next()), (Some(c), None) if crate::hmm::is_hmm_han(c))
+}
+
+fn kind_of(tag: &Tag<'_>) -> Option<EntityKind> {
+    match tag.tag {
+        "nr" | "nrfg" | "nrt" => Some(EntityKind::Person),
+        "ns" => Some(EntityKind::Place),
+        "nt" => Some(EntityKind::Organization),
+        "t" => Some(EntityKind::Time),
+        _ => None,
+    }
+}
+
+/// Whether a single character token can be part of a given name
+fn is_given_name_char(tag: &Tag<'_>) -> bool {
+    is_single_han(tag.word)
+        && !matches!(
+            tag.pos().category(),
+            PosCategory::Particle
+                | PosCategory::Preposition
+                | PosCategory::Conjunction
+                | PosCategory::Pronoun
+                | PosCategory::Punctuation
+                | PosCategory::Classifier
+                | PosCategory::Other
+        )
+}
+
+/// Person name starting at `i`: a person token, or a surname followed by a person
+/// token or by one or two given name characters.
+fn match_person(tags: &[Tag<'_>], i: usize) -> Option<usize> {
+    let tag = &tags[i];
+    let surname = is_surname_token(tag);
+    if let Some(next) = tags.get(i + 1)
+        && surname
+        && kind_of(next) == Some(EntityKind::Person)
+        && next.end - tag.start <= 4
+    {
+        return Some(i + 2);
+    }
+    if kind_of(tag) != Some(EntityKind::Person) {
+        return None;
+    }
+    let mut end = i + 1;
+    if surname {
+        while end < tags.len() && end - i <= 2 && is_given_name_char(&tags[end]) {
+            end += 1;
+        }
+    }
+    Some(end)
+}
+
+/// Organization name ending at an organization token or an organization suffix,
+/// starting with a proper noun at `i`.
+fn match_organization(tags: &[Tag<'_>], i: usize) -> Option<usize> {
+    let first = tags[i].pos();
+    if !(first.is_proper_noun() || first == PosTag::J) {
+        return None;
+    }
+    let mut end = None;
+    for (j, tag) in tags.iter().enumerate().skip(i).take(MAX_ORGANIZATION_TOKENS) {
+        if kind_of(tag) == Some(EntityKind::Organization) || (j > i && ORGANIZATION_SUFFIXES.contains(&tag.word)) {
+            end = Some(j + 1);
+        }
+        let pos = tag.pos();
+        if !(pos.category() == PosCategory::Noun && pos != PosTag::T && pos != PosTag::Tg) {
+            break;
+        }
+    }
+    end
+}
+
+/// Place names starting at `i`, with following places and an administrative suffix
+fn match_place(tags: &[Tag<'_>], i: usize) -> Option<usize> {
+    if kind_of(&tags[i]) != Some(EntityKind::Place) {
+        return None;
+    }
+    let mut end = i + 1;
+    while end < tags.len() && kind_of(&tags[end]) == Some(EntityKind::Place) {
+        end += 1;
+    }
+    if end < tags.len() && PLACE_SUFFIXES.contains(&tags[end].word) {
+        end += 1;
+    }
+    Some(end)
+}
+
+/// Number of tokens of a time unit starting at `i`: a time token, a numeral ending
+/// with a time unit such as `三点`, or a numeral followed by a time unit token.
+fn time_len(tags: &[Tag<'_>], i: usize) -> usize {
+    let tag = &tags[i];
+    if kind_of(tag) == Some(EntityKind::Time) {
+        return 1;
+    }
+    if tag.pos().upos() != Upos::Num {
+        return 0;
+    }
+    match tag.word.chars().last() {
+        Some(last) if tag.word.chars().nth(1).is_some() && TIME_UNITS.contains(last) => 1,
+        _ => match tags.get(i + 1) {
+            Some(next) if next.word.chars().count() == 1 && TIME_UNITS.contains(next.word) => 2,
+            _ => 0,
+        },
+    }
+}
+
+fn match_time(tags: &[Tag<'_>], i: usize) -> Option<usize> {
+    let mut end = i;
+    while end < tags.len() {
+        match time_len(tags, end) {
+            0 => break,
+            len => end += len,
+        }
+    }
+    if end > i { Some(end) } else { None }
+}
+
+impl<'a> Entity<'a> {
+    /// Extracts the named entities of `sentence` from its tags.
+    ///
+    /// `tags` are the output of [`Jieba::tag`](crate::Jieba::tag) or
+    /// [`Jieba::tag_joint`](crate::Jieba::tag_joint) for `sentence`. Adjacent tokens are
+    /// merged into one entity: a surname tagged as a person name or left untagged with a
+    /// given name or a person name, places with an administrative suffix such as
+    /// 省/市/县, proper nouns with an organization suffix such as 公司/大学, and
+    /// consecutive time words and numerals with time units.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jieba_rs::{Entity, EntityKind, Jieba};
+    ///
+    /// let mut jieba = Jieba::empty();
+    /// jieba.add_word("浙江", Some(100), Some("ns"));
+    /// jieba.add_word("杭州", Some(100), Some("ns"));
+    /// jieba.add_word("市", Some(100), Some("n"));
+    ///
+    /// let sentence = "浙江杭州市";
+    /// let tags = jieba.tag(sentence, false);
+    /// let entities = Entity::from_tags(sentence, &tags);
+    /// assert_eq!(entities[0].text, "浙江杭州市");
+    /// assert_eq!(entities[0].kind, EntityKind::Place);
+    /// ```
+    pub fn from_tags(sentence: &'a str, tags: &[Tag<'_>]) -> Vec<Entity<'a>> {
+        let mut entities = Vec::new();
+        let mut i = 0;
+        while i < tags.len() {
+            let found = match_organization(tags, i)
+                .map(|end| (end, EntityKind::Organization))
+                .or_else(|| match_person(tags, i).map(|end| (end, EntityKind::Person)))
+                .or_else(|| match_place(tags, i).map(|end| (end, EntityKind::Place)))
+                .or_else(|| match_time(tags, i).map(|end| (end, EntityKind::Time)));
+            match found {
+                Some((end, kind)) => {
+                    let (first, last) = (&tags[i], &tags[end - 1]);
+                    entities.push(Entity {
+                        text: &sentence[first.byte_start..last.byte_end],
+                        kind,
+                        start: first.start,
+                        end: last.end,
+                        byte_start: first.byte_start,
+                        byte_end: last.byte_end,
+                    });
+                    i = end;
+                }
+                None => i += 1,
+            }
+        }
+        entities
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entities<'a>(sentence: &'a str, words: &[(&str, &'a str)]) -> Vec<(&'a str, EntityKind)> {
+        let tags = Tag::from_pairs(words.iter().copied());
+        assert_eq!(tags.iter().map(|t| t.word).collect::<String>(), sentence);
+        Entity::from_tags(sentence, &tags)
+            .into_iter()
+            .map(|e| (e.text, e.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_person() {
+        assert_eq!(
+            entities("张尧经理", &[("张", "nr"), ("尧", "ng"), ("经理", "n")]),
+            vec![("张尧", EntityKind::Person)]
+        );
+        assert_eq!(
+            entities("欧阳修的", &[("欧阳", "nr"), ("修", "v"), ("的", "uj")]),
+            vec![("欧阳修", EntityKind::Person)]
+        );
+        assert_eq!(
+            entities("王小明说", &[("王", "nr"), ("小明", "nr"), ("说", "v")]),
+            vec![("王小明", EntityKind::Person)]
+        );
+        assert_eq!(
+            entities("王小明说", &[("王", "x"), ("小明", "nr"), ("说", "v")]),
+            vec![("王小明", EntityKind::Person)]
+        );
+    }
+
+    #[test]
+    fn test_person_after_function_word() {
+        assert_eq!(
+            entities("我和张三", &[("我", "r"), ("和", "c"), ("张三", "nr")]),
+            vec![("张三", EntityKind::Person)]
+        );
+        assert_eq!(
+            entities("向李四借书", &[("向", "p"), ("李四", "nr"), ("借书", "v")]),
+            vec![("李四", EntityKind::Person)]
+        );
+        assert_eq!(
+            entities("他的王小明", &[("他", "r"), ("的", "uj"), ("王", "q"), ("小明", "nr")]),
+            vec![("小明", EntityKind::Person)]
+        );
+    }
+
+    #[test]
+    fn test_place_and_organization() {
+        let sentence = "吉林欧亚置业有限公司在北京市海淀区";
+        let words = [
+            ("吉林", "ns"),
+            ("欧亚", "ns"),
+            ("置业", "n"),
+            ("有限公司", "n"),
+            ("在", "p"),
+            ("北京", "ns"),
+            ("市", "n"),
+            ("海淀区", "ns"),
+        ];
+        assert_eq!(
+            entities(sentence, &words),
+            vec![
+                ("吉林欧亚置业有限公司", EntityKind::Organization),
+                ("北京市", EntityKind::Place),
+                ("海淀区", EntityKind::Place),
+            ]
+        );
+
+        let tags = Tag::from_pairs(words);
+        let entity = &Entity::from_tags(sentence, &tags)[1];
+        assert_eq!((entity.start, entity.end), (11, 14));
+        assert_eq!((entity.byte_start, entity.byte_end), (33, 42));
+    }
+
+    #[test]
+    fn test_time() {
+        assert_eq!(
+            entities(
+                "2013年5月下午三点开会",
+                &[
+                    ("2013", "m"),
+                    ("年", "m"),
+                    ("5", "m"),
+                    ("月", "m"),
+                    ("下午", "t"),
+                    ("三点", "m"),
+                    ("开会", "v")
+                ]
+            ),
+            vec![("2013年5月下午三点", EntityKind::Time)]
+        );
+        assert!(entities("三个人", &[("三个", "m"), ("人", "n")]).is_empty());
+    }
+}
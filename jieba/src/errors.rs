@@ -2,6 +2,7 @@ use std::{error, fmt, io};
 
 /// The Error type
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// I/O errors
     Io(io::Error),
@@ -9,6 +10,12 @@ pub enum Error {
     InvalidDictEntry(String),
     /// Invalid HMM model
     InvalidHmmModel(String),
+    /// Invalid POS tagging model
+    InvalidPossegModel(String),
+    /// Invalid normalization conversion table
+    InvalidConversionTable(String),
+    /// Invalid recognizer pattern
+    InvalidPattern(regex::Error),
 }
 
 impl From<io::Error> for Error {
@@ -17,12 +24,21 @@ impl From<io::Error> for Error {
     }
 }
 
+impl From<regex::Error> for Error {
+    fn from(err: regex::Error) -> Self {
+        Self::InvalidPattern(err)
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             Error::Io(ref err) => err.fmt(f),
             Error::InvalidDictEntry(ref err) => write!(f, "invalid dictionary entry: {err}"),
             Error::InvalidHmmModel(ref err) => write!(f, "invalid HMM model: {err}"),
+            Error::InvalidPossegModel(ref err) => write!(f, "invalid POS model: {err}"),
+            Error::InvalidConversionTable(ref err) => write!(f, "invalid conversion table: {err}"),
+            Error::InvalidPattern(ref err) => write!(f, "invalid pattern: {err}"),
         }
     }
 }
@@ -33,6 +49,9 @@ impl error::Error for Error {
             Error::Io(ref err) => Some(err),
             Error::InvalidDictEntry(_) => None,
             Error::InvalidHmmModel(_) => None,
+            Error::InvalidPossegModel(_) => None,
+            Error::InvalidConversionTable(_) => None,
+            Error::InvalidPattern(ref err) => Some(err),
         }
     }
 }
@@ -0,0 +1,322 @@
+use std::fmt;
+use std::io::BufRead;
+
+use crate::errors::Error;
+use crate::{FxHashSet, Jieba};
+
+/// Segmentation errors of one gold sentence
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentenceErrors {
+    /// Zero-based index of the sentence among the evaluated ones, blank sentences included,
+    /// which is its line number in a file evaluated with [`Evaluator::add_reader`]
+    pub index: usize,
+    /// Gold segmentation
+    pub gold: Vec<String>,
+    /// Segmentation produced by `Jieba::cut`
+    pub predicted: Vec<String>,
+    /// Gold words that were not produced
+    pub missed: Vec<String>,
+    /// Produced words that are not in the gold segmentation
+    pub spurious: Vec<String>,
+}
+
+/// Word-level segmentation scores
+///
+/// A word is counted as correct when its character span matches a gold word
+/// exactly. Gold words not in the lexicon of the [`Evaluator`] are out-of-vocabulary
+/// (OOV), the others are in-vocabulary (IV).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Evaluation {
+    /// Number of gold words
+    pub gold_words: usize,
+    /// Number of produced words
+    pub predicted_words: usize,
+    /// Number of correctly produced words
+    pub correct_words: usize,
+    /// Number of gold words not in the lexicon
+    pub oov_words: usize,
+    /// Number of correctly produced words not in the lexicon
+    pub correct_oov_words: usize,
+    /// Number of gold words in the lexicon
+    pub iv_words: usize,
+    /// Number of correctly produced words in the lexicon
+    pub correct_iv_words: usize,
+    /// Sentences with at least one segmentation error
+    pub errors: Vec<SentenceErrors>,
+}
+
+#[inline]
+fn ratio(a: usize, b: usize) -> f64 {
+    if b == 0 { 0.0 } else { a as f64 / b as f64 }
+}
+
+impl Evaluation {
+    /// Fraction of produced words that are correct
+    pub fn precision(&self) -> f64 {
+        ratio(self.correct_words, self.predicted_words)
+    }
+
+    /// Fraction of gold words that are produced
+    pub fn recall(&self) -> f64 {
+        ratio(self.correct_words, self.gold_words)
+    }
+
+    /// Harmonic mean of precision and recall
+    pub fn f1(&self) -> f64 {
+        let (p, r) = (self.precision(), self.recall());
+        if p + r == 0.0 { 0.0 } else { 2.0 * p * r / (p + r) }
+    }
+
+    /// Fraction of gold words that are out of vocabulary
+    pub fn oov_rate(&self) -> f64 {
+        ratio(self.oov_words, self.gold_words)
+    }
+
+    /// Fraction of out-of-vocabulary gold words that are produced
+    pub fn oov_recall(&self) -> f64 {
+        ratio(self.correct_oov_words, self.oov_words)
+    }
+
+    /// Fraction of in-vocabulary gold words that are produced
+    pub fn iv_recall(&self) -> f64 {
+        ratio(self.correct_iv_words, self.iv_words)
+    }
+}
+
+impl fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "P={:.4} R={:.4} F1={:.4} OOV-Rate={:.4} OOV-R={:.4} IV-R={:.4}",
+            self.precision(),
+            self.recall(),
+            self.f1(),
+            self.oov_rate(),
+            self.oov_recall(),
+            self.iv_recall()
+        )
+    }
+}
+
+/// Evaluates `Jieba::cut` against a gold segmentation in the SIGHAN bakeoff format
+///
+/// Each gold sentence contains words separated by whitespace. The input to
+/// segment is the gold sentence with the whitespace removed, which is what the
+/// bakeoff test files contain.
+///
+/// Gold words are split into in-vocabulary and out-of-vocabulary words with a fixed
+/// lexicon, usually the words of the training data, set with [`Evaluator::set_lexicon`]
+/// or [`Evaluator::load_lexicon`]. Without a lexicon, the dictionary of the evaluated
+/// [`Jieba`] is used, so the OOV split then changes with the dictionary.
+///
+/// # Examples
+///
+/// ```
+/// use jieba_rs::{Evaluator, Jieba};
+///
+/// let mut jieba = Jieba::empty();
+/// jieba.add_word("我们", Some(100), None);
+/// jieba.add_word("中国", Some(100), None);
+///
+/// let mut evaluator = Evaluator::new(&jieba, false);
+/// evaluator.set_lexicon(["我们", "是", "中国"]);
+/// evaluator.add_sentence("我们 是 中国 人");
+/// let evaluation = evaluator.evaluation();
+/// assert_eq!(evaluation.f1(), 1.0);
+/// assert_eq!(evaluation.oov_words, 1);
+/// ```
+#[derive(Debug)]
+pub struct Evaluator<'a> {
+    jieba: &'a Jieba,
+    hmm: bool,
+    lexicon: Option<FxHashSet<String>>,
+    evaluation: Evaluation,
+    sentences: usize,
+}
+
+impl<'a> Evaluator<'a> {
+    /// Creates an Evaluator cutting with `jieba`, with HMM enabled or not
+    pub fn new(jieba: &'a Jieba, hmm: bool) -> Self {
+        Evaluator {
+            jieba,
+            hmm,
+            lexicon: None,
+            evaluation: Evaluation::default(),
+            sentences: 0,
+        }
+    }
+
+    /// Sets the words that are in vocabulary, replacing the dictionary of the evaluated
+    /// [`Jieba`] for the OOV split
+    pub fn set_lexicon<I, S>(&mut self, words: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.lexicon = Some(words.into_iter().map(Into::into).collect());
+    }
+
+    /// Adds the whitespace separated words of a word list or of a segmented training file
+    /// to the lexicon
+    pub fn load_lexicon<R: BufRead>(&mut self, reader: &mut R) -> Result<(), Error> {
+        let lexicon = self.lexicon.get_or_insert_with(FxHashSet::default);
+        for line in reader.lines() {
+            lexicon.extend(line?.split_whitespace().map(String::from));
+        }
+        Ok(())
+    }
+
+    /// Whether `word` is in vocabulary
+    fn is_iv(&self, word: &str) -> bool {
+        match &self.lexicon {
+            Some(lexicon) => lexicon.contains(word),
+            None => self.jieba.has_word(word),
+        }
+    }
+
+    /// Evaluates one gold sentence, blank sentences are skipped but counted in the
+    /// [`SentenceErrors::index`]
+    pub fn add_sentence(&mut self, gold: &str) {
+        let index = self.sentences;
+        self.sentences += 1;
+        let gold_words: Vec<&str> = gold.split_whitespace().collect();
+        if gold_words.is_empty() {
+            return;
+        }
+        let text: String = gold_words.concat();
+        let predicted: Vec<&str> = self.jieba.cut(&text, self.hmm).into_iter().map(|t| t.word).collect();
+
+        let gold_spans = spans(&gold_words);
+        let predicted_spans = spans(&predicted);
+
+        let iv: Vec<bool> = gold_words.iter().map(|word| self.is_iv(word)).collect();
+        let evaluation = &mut self.evaluation;
+        evaluation.gold_words += gold_words.len();
+        evaluation.predicted_words += predicted.len();
+
+        let mut missed = Vec::new();
+        for ((word, span), iv) in gold_words.iter().zip(&gold_spans).zip(iv) {
+            let correct = predicted_spans.binary_search(span).is_ok();
+            if correct {
+                evaluation.correct_words += 1;
+            } else {
+                missed.push(word.to_string());
+            }
+            if iv {
+                evaluation.iv_words += 1;
+                evaluation.correct_iv_words += correct as usize;
+            } else {
+                evaluation.oov_words += 1;
+                evaluation.correct_oov_words += correct as usize;
+            }
+        }
+
+        let spurious: Vec<String> = predicted
+            .iter()
+            .zip(&predicted_spans)
+            .filter(|(_, span)| gold_spans.binary_search(span).is_err())
+            .map(|(word, _)| word.to_string())
+            .collect();
+
+        if !missed.is_empty() || !spurious.is_empty() {
+            evaluation.errors.push(SentenceErrors {
+                index,
+                gold: gold_words.iter().map(|w| w.to_string()).collect(),
+                predicted: predicted.iter().map(|w| w.to_string()).collect(),
+                missed,
+                spurious,
+            });
+        }
+    }
+
+    /// Evaluates a gold file, one sentence per line
+    pub fn add_reader<R: BufRead>(&mut self, reader: &mut R) -> Result<(), Error> {
+        for line in reader.lines() {
+            self.add_sentence(&line?);
+        }
+        Ok(())
+    }
+
+    /// Scores of the sentences evaluated so far
+    pub fn evaluation(&self) -> &Evaluation {
+        &self.evaluation
+    }
+
+    /// Consumes the Evaluator and returns the scores
+    pub fn into_evaluation(self) -> Evaluation {
+        self.evaluation
+    }
+}
+
+/// Character spans of consecutive words, in ascending order
+fn spans(words: &[&str]) -> Vec<(usize, usize)> {
+    let mut offset = 0;
+    words
+        .iter()
+        .map(|w| {
+            let start = offset;
+            offset += w.chars().count();
+            (start, offset)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate() {
+        let mut jieba = Jieba::empty();
+        jieba.add_word("我们", Some(100), None);
+        jieba.add_word("中国", Some(100), None);
+        jieba.add_word("人", Some(50), None);
+
+        let gold = "我们 是 中国 人\n\n他 来到  北京\n";
+        let mut evaluator = Evaluator::new(&jieba, false);
+        evaluator.add_reader(&mut gold.as_bytes()).unwrap();
+        let evaluation = evaluator.into_evaluation();
+
+        assert_eq!(evaluation.gold_words, 7);
+        assert_eq!(evaluation.predicted_words, 9);
+        assert_eq!(evaluation.correct_words, 5);
+        assert_eq!(evaluation.precision(), 5.0 / 9.0);
+        assert_eq!(evaluation.recall(), 5.0 / 7.0);
+        assert_eq!(evaluation.oov_recall(), 0.5);
+        assert_eq!(evaluation.iv_recall(), 1.0);
+        assert_eq!(
+            evaluation.errors,
+            vec![SentenceErrors {
+                index: 2,
+                gold: vec!["他".into(), "来到".into(), "北京".into()],
+                predicted: vec!["他".into(), "来".into(), "到".into(), "北".into(), "京".into()],
+                missed: vec!["来到".into(), "北京".into()],
+                spurious: vec!["来".into(), "到".into(), "北".into(), "京".into()],
+            }]
+        );
+        assert_eq!(
+            evaluation.to_string(),
+            "P=0.5556 R=0.7143 F1=0.6250 OOV-Rate=0.5714 OOV-R=0.5000 IV-R=1.0000"
+        );
+    }
+
+    #[test]
+    fn test_evaluate_with_lexicon() {
+        let mut jieba = Jieba::empty();
+        jieba.add_word("北京", Some(100), None);
+
+        let mut evaluator = Evaluator::new(&jieba, false);
+        evaluator.load_lexicon(&mut "他 来到\n来到 上海\n".as_bytes()).unwrap();
+        evaluator.add_sentence("他 来到 北京");
+        let evaluation = evaluator.evaluation();
+        assert_eq!((evaluation.iv_words, evaluation.correct_iv_words), (2, 1));
+        assert_eq!((evaluation.oov_words, evaluation.correct_oov_words), (1, 1));
+    }
+
+    #[test]
+    fn test_evaluate_empty() {
+        let jieba = Jieba::empty();
+        let evaluator = Evaluator::new(&jieba, true);
+        assert_eq!(evaluator.evaluation().f1(), 0.0);
+    }
+}
@@ -1,5 +1,5 @@
 use std::cmp::Ordering;
-use std::io::BufRead;
+use std::io::{BufRead, Write};
 
 use regex::Regex;
 
@@ -14,7 +14,7 @@ thread_local! {
 
 /// HMM-specific CJK range `[\u{4E00}-\u{9FD5}]`
 #[inline]
-fn is_hmm_han(c: char) -> bool {
+pub(crate) fn is_hmm_han(c: char) -> bool {
     matches!(c, '\u{4E00}'..='\u{9FD5}')
 }
 
@@ -279,23 +279,31 @@ pub(crate) fn cut_with_allocated_memory<'a>(
                     words.push(block);
                 }
             } else {
-                let skip_splitter = HmmSkipSplitter::new(re_skip, block);
-                for x in skip_splitter {
-                    if x.is_empty() {
-                        continue;
-                    }
-                    words.push(x);
-                }
+                cut_skip(re_skip, block, words);
             }
         }
     })
 }
 
+/// Split a non-Han block into alphanumeric runs and the text between them
+fn cut_skip<'a>(re_skip: &Regex, block: &'a str, words: &mut Vec<&'a str>) {
+    for x in HmmSkipSplitter::new(re_skip, block) {
+        if !x.is_empty() {
+            words.push(x);
+        }
+    }
+}
+
+/// Split a non-Han block the same way [`cut_with_allocated_memory`] does
+pub(crate) fn cut_non_han<'a>(block: &'a str, words: &mut Vec<&'a str>) {
+    RE_SKIP.with(|re_skip| cut_skip(re_skip, block, words))
+}
+
 /// A runtime-loadable HMM model for custom segmentation.
 ///
-/// This allows loading HMM parameters trained with `scripts/train_hmm.py`
-/// instead of using the compile-time embedded model.
-#[derive(Debug, Clone)]
+/// This allows loading HMM parameters trained with [`HmmModel::train`] or
+/// `scripts/train_hmm.py` instead of using the compile-time embedded model.
+#[derive(Debug, Clone, PartialEq)]
 pub struct HmmModel {
     initial_probs: [f64; NUM_STATES],
     trans_probs: [[f64; NUM_STATES]; NUM_STATES],
@@ -324,7 +332,7 @@ impl HmmParams for HmmModel {
 impl HmmModel {
     /// Load an HMM model from a reader in the `hmm.model` file format.
     ///
-    /// The format is compatible with the output of `scripts/train_hmm.py`.
+    /// The format is compatible with the output of [`HmmModel::save`] and `scripts/train_hmm.py`.
     pub fn load<R: BufRead>(reader: &mut R) -> Result<Self, Error> {
         let mut data_lines = Vec::new();
         let mut buf = String::new();
@@ -386,6 +394,133 @@ impl HmmModel {
         })
     }
 
+    /// Train an HMM model from word-segmented sentences.
+    ///
+    /// Each sentence contains words separated by whitespace. Every word is labeled
+    /// with Begin/Middle/End/Single states and the initial, transition and emission
+    /// log-probabilities are estimated the same way as `scripts/train_hmm.py` does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jieba_rs::{HmmModel, Jieba};
+    ///
+    /// let corpus = ["中国 人民 站 起来 了", "我 是 中国 人"];
+    /// let model = HmmModel::train(corpus).unwrap();
+    ///
+    /// let mut jieba = Jieba::empty();
+    /// jieba.set_hmm_model(model);
+    /// ```
+    pub fn train<I, S>(sentences: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut start_count = [0u64; NUM_STATES];
+        let mut trans_count = [[0u64; NUM_STATES]; NUM_STATES];
+        let mut emit_count: [FxHashMap<char, u64>; NUM_STATES] = Default::default();
+
+        for sentence in sentences {
+            let mut prev: Option<State> = None;
+            for word in sentence.as_ref().split_whitespace() {
+                let char_len = word.chars().count();
+                for (i, ch) in word.chars().enumerate() {
+                    let state = if char_len == 1 {
+                        State::Single
+                    } else if i == 0 {
+                        State::Begin
+                    } else if i == char_len - 1 {
+                        State::End
+                    } else {
+                        State::Middle
+                    };
+                    match prev {
+                        None => start_count[state as usize] += 1,
+                        Some(p) => trans_count[p as usize][state as usize] += 1,
+                    }
+                    *emit_count[state as usize].entry(ch).or_insert(0) += 1;
+                    prev = Some(state);
+                }
+            }
+        }
+
+        let total_start: u64 = start_count.iter().sum();
+        if total_start == 0 {
+            return Err(Error::InvalidHmmModel("empty training corpus".to_string()));
+        }
+
+        let log_prob = |count: u64, total: u64| {
+            if count > 0 {
+                (count as f64 / total as f64).ln()
+            } else {
+                MIN_FLOAT
+            }
+        };
+
+        let initial_probs = start_count.map(|count| log_prob(count, total_start));
+        let trans_probs = trans_count.map(|row| {
+            let total: u64 = row.iter().sum();
+            row.map(|count| log_prob(count, total))
+        });
+        let emit_probs = emit_count.map(|counts| {
+            let total: u64 = counts.values().sum();
+            counts
+                .into_iter()
+                .map(|(ch, count)| (ch.to_string().into_boxed_str(), log_prob(count, total)))
+                .collect()
+        });
+
+        Ok(HmmModel {
+            initial_probs,
+            trans_probs,
+            emit_probs,
+        })
+    }
+
+    /// Save the HMM model in the `hmm.model` file format read by [`HmmModel::load`].
+    ///
+    /// Emission entries for characters that cannot be represented in the format
+    /// (`,` and whitespace) are skipped.
+    pub fn save<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        const STATE_NAMES: [&str; NUM_STATES] = ["B", "E", "M", "S"];
+
+        writeln!(writer, "#")?;
+        for (i, name) in STATE_NAMES.iter().enumerate() {
+            writeln!(writer, "#{i}:{name}")?;
+        }
+        writeln!(writer, "#")?;
+        writeln!(writer, "#prob_start")?;
+        writeln!(writer, "{}", Self::format_prob_line(&self.initial_probs))?;
+        writeln!(writer, "#prob_trans 4x4 matrix")?;
+        for row in &self.trans_probs {
+            writeln!(writer, "{}", Self::format_prob_line(row))?;
+        }
+        writeln!(writer, "#prob_emit 4 lines")?;
+        for (name, emit) in STATE_NAMES.iter().zip(&self.emit_probs) {
+            let mut pairs: Vec<(&str, f64)> = emit
+                .iter()
+                .filter(|(ch, _)| !ch.is_empty() && !ch.contains(',') && !ch.contains(char::is_whitespace))
+                .map(|(ch, prob)| (&**ch, *prob))
+                .collect();
+            if pairs.is_empty() {
+                return Err(Error::InvalidHmmModel(format!(
+                    "state {name} has no emission probabilities"
+                )));
+            }
+            // A line starting with `#` would be read back as a comment
+            pairs.sort_unstable_by(|a, b| (a.0.starts_with('#'), a.0).cmp(&(b.0.starts_with('#'), b.0)));
+            writeln!(writer, "#{name}")?;
+            let line: Vec<String> = pairs.iter().map(|(ch, prob)| format!("{ch}:{prob:?}")).collect();
+            writeln!(writer, "{}", line.join(","))?;
+        }
+        Ok(())
+    }
+
+    fn format_prob_line(probs: &[f64; NUM_STATES]) -> String {
+        let vals: Vec<String> = probs.iter().map(|p| format!("{p:?}")).collect();
+        vals.join(" ")
+    }
+
     fn parse_prob_line(line: &str, context: &str) -> Result<[f64; NUM_STATES], Error> {
         let vals: Vec<f64> = line
             .split_whitespace()
@@ -412,7 +547,7 @@ pub(crate) fn builtin_hmm() -> BuiltinHmm {
 mod tests {
     use expect_test::expect;
 
-    use super::{BuiltinHmm, HmmContext, cut_with_allocated_memory, viterbi};
+    use super::{BuiltinHmm, HmmContext, HmmModel, MIN_FLOAT, cut_with_allocated_memory, viterbi};
 
     fn cut<'a>(sentence: &'a str, words: &mut Vec<&'a str>) {
         let mut hmm_context = HmmContext::default();
@@ -439,4 +574,36 @@ mod tests {
         cut(sentence, &mut words);
         expect![[r#"["小明", "硕士", "毕业于", "中国", "科学院", "计算", "所"]"#]].assert_eq(&format!("{:?}", words));
     }
+
+    #[test]
+    fn test_train_hmm() {
+        let model = HmmModel::train(["中国 人民 站 起来 了", "我 是 中国 人"]).unwrap();
+
+        // Sentences start with B (中国) and S (我)
+        assert_eq!(model.initial_probs[0], 0.5f64.ln());
+        assert_eq!(model.initial_probs[1], MIN_FLOAT);
+        assert_eq!(model.initial_probs[3], 0.5f64.ln());
+        // Every B is followed by E
+        assert_eq!(model.trans_probs[0][1], 0.0);
+        assert_eq!(model.trans_probs[0][2], MIN_FLOAT);
+        // S emissions: 站 了 我 是 人
+        assert_eq!(model.emit_probs[3]["站"], 0.2f64.ln());
+
+        assert!(HmmModel::train(["", "  "]).is_err());
+    }
+
+    #[test]
+    fn test_save_load_roundtrip() {
+        let hmm_data = include_str!("../../jieba-macros/src/hmm.model");
+        let model = HmmModel::load(&mut hmm_data.as_bytes()).unwrap();
+
+        let mut saved = Vec::new();
+        model.save(&mut saved).unwrap();
+        let loaded = HmmModel::load(&mut saved.as_slice()).unwrap();
+        assert_eq!(loaded, model);
+
+        // A model without Middle emissions can't be represented
+        let model = HmmModel::train(["中国 人民"]).unwrap();
+        assert!(model.save(&mut Vec::new()).is_err());
+    }
 }
@@ -0,0 +1,239 @@
+use std::io::{self, Write};
+
+use super::KeywordExtractConfig;
+use crate::FxHashMap;
+use crate::Jieba;
+
+/// Number of documents segmented per batch by [`IdfBuilder::add_documents`]
+const BATCH_SIZE: usize = 1_000;
+
+/// Builds an IDF dictionary for [`TfIdf`](crate::TfIdf) from a corpus of documents
+///
+/// Documents are segmented with a [`Jieba`] instance, keeping the words that pass the stop
+/// word and length filters of a [`KeywordExtractConfig`], with its HMM setting. The IDF of
+/// a word seen in `df` of `n` documents is smoothed as `ln((1 + n) / (1 + df)) + 1`, so that
+/// it is positive and defined for any word. Words containing whitespace are skipped, as
+/// the dictionary format cannot hold them.
+///
+/// Require `tfidf` feature to be enabled.
+///
+/// # Examples
+///
+/// ```
+/// use jieba_rs::{IdfBuilder, Jieba, KeywordExtractConfig, TfIdf};
+///
+/// let mut jieba = Jieba::empty();
+/// jieba.add_word("区块链", Some(100), Some("n"));
+/// jieba.add_word("技术", Some(100), Some("n"));
+/// let config = KeywordExtractConfig::default();
+///
+/// let mut builder = IdfBuilder::new().min_document_frequency(2);
+/// builder.add_documents(&jieba, &config, ["区块链技术", "区块链", "技术"]);
+/// assert_eq!(builder.document_count(), 3);
+/// assert_eq!(builder.document_frequency("区块链"), 2);
+///
+/// let mut dict = Vec::new();
+/// builder.write_dict(&mut dict).unwrap();
+/// let tfidf = TfIdf::new(Some(&mut dict.as_slice()), config);
+/// ```
+#[derive(Debug, Clone)]
+pub struct IdfBuilder {
+    doc_freq: FxHashMap<String, usize>,
+    documents: usize,
+    min_document_frequency: usize,
+}
+
+impl Default for IdfBuilder {
+    fn default() -> Self {
+        IdfBuilder {
+            doc_freq: FxHashMap::default(),
+            documents: 0,
+            min_document_frequency: 1,
+        }
+    }
+}
+
+impl IdfBuilder {
+    /// Creates an empty IdfBuilder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the minimum number of documents a word must be seen in to be written,
+    /// 1 by default
+    pub fn min_document_frequency(mut self, min_document_frequency: usize) -> Self {
+        self.min_document_frequency = min_document_frequency;
+        self
+    }
+
+    /// Counts the words of a document
+    pub fn add_document(&mut self, jieba: &Jieba, config: &KeywordExtractConfig, document: &str) {
+        let mut words: Vec<&str> = jieba
+            .cut(document, config.use_hmm())
+            .into_iter()
+            .map(|token| token.word)
+            .filter(|word| config.is_keyword(word) && !word.contains(char::is_whitespace))
+            .collect();
+        words.sort_unstable();
+        words.dedup();
+        for word in words {
+            match self.doc_freq.get_mut(word) {
+                Some(freq) => *freq += 1,
+                None => {
+                    self.doc_freq.insert(word.to_string(), 1);
+                }
+            }
+        }
+        self.documents += 1;
+    }
+
+    /// Counts the words of many documents.
+    ///
+    /// Documents are segmented in parallel when the `parallel` feature is enabled.
+    pub fn add_documents<I>(&mut self, jieba: &Jieba, config: &KeywordExtractConfig, documents: I)
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str> + Sync,
+    {
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        for document in documents {
+            batch.push(document);
+            if batch.len() == BATCH_SIZE {
+                self.add_batch(jieba, config, &batch);
+                batch.clear();
+            }
+        }
+        self.add_batch(jieba, config, &batch);
+    }
+
+    fn add_batch<S: AsRef<str> + Sync>(&mut self, jieba: &Jieba, config: &KeywordExtractConfig, documents: &[S]) {
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+
+            let counted = documents
+                .par_iter()
+                .fold(IdfBuilder::new, |mut builder, document| {
+                    builder.add_document(jieba, config, document.as_ref());
+                    builder
+                })
+                .reduce(IdfBuilder::new, |mut a, b| {
+                    a.merge(b);
+                    a
+                });
+            self.merge(counted);
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        for document in documents {
+            self.add_document(jieba, config, document.as_ref());
+        }
+    }
+
+    /// Adds the counts of `other` to this builder
+    pub fn merge(&mut self, other: IdfBuilder) {
+        for (word, freq) in other.doc_freq {
+            *self.doc_freq.entry(word).or_insert(0) += freq;
+        }
+        self.documents += other.documents;
+    }
+
+    /// Number of counted documents
+    pub fn document_count(&self) -> usize {
+        self.documents
+    }
+
+    /// Number of documents `word` was seen in
+    pub fn document_frequency(&self, word: &str) -> usize {
+        self.doc_freq.get(word).copied().unwrap_or(0)
+    }
+
+    /// Smoothed IDF of `word`
+    pub fn idf(&self, word: &str) -> f64 {
+        smoothed_idf(self.documents, self.document_frequency(word))
+    }
+
+    /// Words seen in enough documents with their IDF, ordered by word
+    pub fn entries(&self) -> Vec<(&str, f64)> {
+        let mut entries: Vec<(&str, f64)> = self
+            .doc_freq
+            .iter()
+            .filter(|&(_, &df)| df >= self.min_document_frequency)
+            .map(|(word, &df)| (word.as_str(), smoothed_idf(self.documents, df)))
+            .collect();
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        entries
+    }
+
+    /// Writes the entries as `word idf` lines that [`TfIdf::load_dict`](crate::TfIdf::load_dict)
+    /// accepts.
+    pub fn write_dict<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for (word, idf) in self.entries() {
+            writeln!(writer, "{word} {idf}")?;
+        }
+        Ok(())
+    }
+}
+
+/// IDF of a word seen in `df` of `documents` documents, smoothed as if one more document
+/// contained every word
+pub(crate) fn smoothed_idf(documents: usize, df: usize) -> f64 {
+    ((1 + documents) as f64 / (1 + df) as f64).ln() + 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idf_builder() {
+        let mut jieba = Jieba::empty();
+        jieba.add_word("天气", Some(100), Some("n"));
+        jieba.add_word("很好", Some(100), Some("a"));
+        jieba.add_word("the", Some(100), Some("eng"));
+        let config = KeywordExtractConfig::default();
+
+        let mut builder = IdfBuilder::new();
+        builder.add_documents(&jieba, &config, ["天气很好，天气", "天气 the", "很好"].iter());
+        assert_eq!(builder.document_count(), 3);
+        assert_eq!(builder.document_frequency("天气"), 2);
+        assert_eq!(builder.document_frequency("the"), 0);
+        assert!((builder.idf("天气") - ((4.0f64 / 3.0).ln() + 1.0)).abs() < 1e-12);
+        assert!((builder.idf("未见") - (4.0f64.ln() + 1.0)).abs() < 1e-12);
+
+        let mut other = IdfBuilder::new();
+        other.add_document(&jieba, &config, "天气");
+        builder.merge(other);
+        assert_eq!(builder.document_frequency("天气"), 3);
+
+        let builder = builder.min_document_frequency(3);
+        let mut dict = Vec::new();
+        builder.write_dict(&mut dict).unwrap();
+        let dict = String::from_utf8(dict).unwrap();
+        assert_eq!(dict, format!("天气 {}\n", builder.idf("天气")));
+    }
+
+    #[test]
+    fn test_idf_builder_edge_cases() {
+        let mut jieba = Jieba::empty();
+        jieba.add_word("New York", Some(100), Some("ns"));
+        jieba.add_word("纽约", Some(100), Some("ns"));
+        let config = KeywordExtractConfig::default();
+
+        let builder = IdfBuilder::new();
+        assert_eq!(builder.min_document_frequency, 1);
+        let mut dict = Vec::new();
+        builder.write_dict(&mut dict).unwrap();
+        assert!(dict.is_empty());
+        let mut tfidf = crate::TfIdf::new(Some(&mut dict.as_slice()), config.clone());
+        tfidf.load_dict(&mut "纽约 2.0\n".as_bytes()).unwrap();
+        tfidf.load_dict(&mut dict.as_slice()).unwrap();
+        assert_eq!(tfidf.idf("未见"), 2.0);
+
+        let mut builder = IdfBuilder::new();
+        builder.add_document(&jieba, &config, "New York纽约");
+        assert_eq!(builder.document_frequency("New York"), 0);
+        let words: Vec<&str> = builder.entries().into_iter().map(|(word, _)| word).collect();
+        assert_eq!(words, vec!["纽约"]);
+    }
+}
@@ -1,8 +1,10 @@
-use crate::Jieba;
+use crate::{Document, Jieba, PosTag, Tag};
 
 use std::collections::BTreeSet;
 use std::sync::LazyLock;
 
+#[cfg(feature = "tfidf")]
+pub mod idf_builder;
 #[cfg(feature = "textrank")]
 pub mod textrank;
 #[cfg(feature = "tfidf")]
@@ -25,6 +27,44 @@ pub struct Keyword {
     pub keyword: String,
     pub weight: f64,
     pub tag: String,
+    /// Spans of the occurrences of the keyword counted by the extractor, in order
+    pub spans: Vec<KeywordSpan>,
+}
+
+impl Keyword {
+    /// Typed POS tag of the keyword
+    pub fn pos(&self) -> PosTag {
+        PosTag::new(&self.tag)
+    }
+
+    /// Term frequency of the keyword, its number of occurrences
+    pub fn frequency(&self) -> usize {
+        self.spans.len()
+    }
+}
+
+/// Position of an occurrence of a keyword, with the offsets of its [`Tag`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeywordSpan {
+    /// Unicode start position of the occurrence
+    pub start: usize,
+    /// Unicode end position of the occurrence
+    pub end: usize,
+    /// Byte start position of the occurrence
+    pub byte_start: usize,
+    /// Byte end position of the occurrence
+    pub byte_end: usize,
+}
+
+impl From<&Tag<'_>> for KeywordSpan {
+    fn from(tag: &Tag<'_>) -> Self {
+        KeywordSpan {
+            start: tag.start,
+            end: tag.end,
+            byte_start: tag.byte_start,
+            byte_end: tag.byte_end,
+        }
+    }
 }
 
 /// Creates a KeywordExtractConfig state that contains filter criteria as well as segmentation
@@ -239,4 +279,77 @@ impl KeywordExtractConfigBuilder {
 /// Extracts keywords from a given sentence with the Jieba instance.
 pub trait KeywordExtract {
     fn extract_keywords(&self, jieba: &Jieba, sentence: &str, top_k: usize, allowed_pos: Vec<String>) -> Vec<Keyword>;
+
+    /// Same as `extract_keywords`, with the tokens and tags of an already segmented `document`.
+    ///
+    /// The segmentation settings of the extractor are ignored. If the document is not tagged,
+    /// `allowed_pos` must be empty for keywords to be found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jieba_rs::{DocumentConfig, Jieba, KeywordExtract, TextRank};
+    ///
+    /// let mut jieba = Jieba::empty();
+    /// jieba.add_word("北京", Some(100), Some("ns"));
+    /// jieba.add_word("欢迎", Some(100), Some("v"));
+    ///
+    /// let document = jieba.document("北京欢迎你", DocumentConfig::new());
+    /// let keywords = TextRank::default().extract_keywords_from_document(&document, 5, vec!["ns".to_string()]);
+    /// assert_eq!(keywords[0].keyword, "北京");
+    /// ```
+    fn extract_keywords_from_document(
+        &self,
+        document: &Document,
+        top_k: usize,
+        allowed_pos: Vec<String>,
+    ) -> Vec<Keyword> {
+        let tags: Vec<Tag> = document.tags().collect();
+        self.extract_keywords_from_tags(&tags, top_k, allowed_pos)
+    }
+
+    /// Same as `extract_keywords`, with words already segmented and tagged, by Jieba or by
+    /// another segmenter.
+    ///
+    /// The segmentation settings of the extractor are ignored. [`Tag::from_pairs`] builds the
+    /// tags of `(word, tag)` pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jieba_rs::{KeywordExtract, Tag, TextRank};
+    ///
+    /// let tags = Tag::from_pairs([("北京", "ns"), ("欢迎", "v"), ("你", "r")]);
+    /// let keywords = TextRank::default().extract_keywords_from_tags(&tags, 5, vec!["ns".to_string()]);
+    /// assert_eq!(keywords[0].keyword, "北京");
+    /// assert_eq!(keywords[0].frequency(), 1);
+    /// assert_eq!((keywords[0].spans[0].start, keywords[0].spans[0].end), (0, 2));
+    /// ```
+    fn extract_keywords_from_tags(&self, tags: &[Tag], top_k: usize, allowed_pos: Vec<String>) -> Vec<Keyword>;
+
+    /// Same as `extract_keywords`, with typed `allowed_pos` tags.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jieba_rs::{Jieba, KeywordExtract, PosCategory, PosTag, TextRank};
+    ///
+    /// let mut jieba = Jieba::empty();
+    /// jieba.add_word("北京", Some(100), Some("ns"));
+    /// jieba.add_word("欢迎", Some(100), Some("v"));
+    ///
+    /// let nouns: Vec<PosTag> = PosTag::known().filter(|t| t.category() == PosCategory::Noun).collect();
+    /// let keywords = TextRank::default().extract_keywords_with_pos(&jieba, "北京欢迎你", 5, &nouns);
+    /// assert!(keywords.iter().all(|k| k.pos().category() == PosCategory::Noun));
+    /// ```
+    fn extract_keywords_with_pos(
+        &self,
+        jieba: &Jieba,
+        sentence: &str,
+        top_k: usize,
+        allowed_pos: &[PosTag],
+    ) -> Vec<Keyword> {
+        let allowed_pos = allowed_pos.iter().map(|tag| tag.to_string()).collect();
+        self.extract_keywords(jieba, sentence, top_k, allowed_pos)
+    }
 }
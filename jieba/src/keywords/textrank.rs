@@ -3,9 +3,9 @@ use std::collections::{BTreeSet, BinaryHeap};
 
 use ordered_float::OrderedFloat;
 
-use super::{Keyword, KeywordExtract, KeywordExtractConfig, KeywordExtractConfigBuilder};
+use super::{Keyword, KeywordExtract, KeywordExtractConfig, KeywordExtractConfigBuilder, KeywordSpan};
 use crate::FxHashMap as HashMap;
-use crate::Jieba;
+use crate::{Jieba, Tag};
 
 type Weight = f64;
 
@@ -143,6 +143,10 @@ impl KeywordExtract for TextRank {
     /// ```
     fn extract_keywords(&self, jieba: &Jieba, sentence: &str, top_k: usize, allowed_pos: Vec<String>) -> Vec<Keyword> {
         let tags = jieba.tag(sentence, self.config.use_hmm());
+        self.extract_keywords_from_tags(&tags, top_k, allowed_pos)
+    }
+
+    fn extract_keywords_from_tags(&self, tags: &[Tag], top_k: usize, allowed_pos: Vec<String>) -> Vec<Keyword> {
         let mut allowed_pos_set = BTreeSet::new();
 
         for s in allowed_pos {
@@ -154,16 +158,19 @@ impl KeywordExtract for TextRank {
         let mut word2tag: HashMap<&str, &str> =
             HashMap::with_capacity_and_hasher(tags.len() / 2, rustc_hash::FxBuildHasher);
         let mut unique_words = Vec::with_capacity(tags.len() / 2);
-        for t in &tags {
+        let mut occurrences: Vec<Vec<KeywordSpan>> = Vec::with_capacity(tags.len() / 2);
+        for t in tags {
             if !allowed_pos_set.is_empty() && !allowed_pos_set.contains(t.tag) {
                 continue;
             }
 
             word2tag.entry(t.word).or_insert(t.tag);
-            if !word2id.contains_key(t.word) {
+            let id = *word2id.entry(t.word).or_insert_with(|| {
                 unique_words.push(t.word);
-                word2id.insert(t.word, unique_words.len() - 1);
-            }
+                occurrences.push(Vec::new());
+                unique_words.len() - 1
+            });
+            occurrences[id].push(KeywordSpan::from(t));
         }
 
         let mut cooccurence: HashMap<(usize, usize), usize> = HashMap::default();
@@ -222,6 +229,7 @@ impl KeywordExtract for TextRank {
                     keyword: unique_words[w.word_id].to_string(),
                     weight: w.rank.into_inner(),
                     tag: String::from(*word2tag.get(unique_words[w.word_id]).unwrap_or(&"")),
+                    spans: std::mem::take(&mut occurrences[w.word_id]),
                 });
             }
         }
@@ -261,4 +269,14 @@ mod tests {
         let diagram = StateDiagram::new(10);
         assert_eq!(diagram.g.len(), 10);
     }
+
+    #[test]
+    fn test_keyword_spans() {
+        let tags = Tag::from_pairs([("天气", "n"), ("不好", "a"), ("，", "x"), ("天气", "n"), ("真好", "a")]);
+        let keywords = TextRank::default().extract_keywords_from_tags(&tags, 10, vec!["n".to_string()]);
+        assert_eq!(keywords.len(), 1);
+        assert_eq!(keywords[0].frequency(), 2);
+        let spans: Vec<(usize, usize)> = keywords[0].spans.iter().map(|s| (s.start, s.byte_start)).collect();
+        assert_eq!(spans, vec![(0, 0), (5, 15)]);
+    }
 }
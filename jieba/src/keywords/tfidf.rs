@@ -1,13 +1,13 @@
 use std::cmp::Ordering;
-use std::collections::{BTreeSet, BinaryHeap};
-use std::io::{self, BufRead, BufReader};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, VecDeque};
+use std::io::{self, BufRead, BufReader, Write};
 
 use include_flate::flate;
 use ordered_float::OrderedFloat;
 
-use super::{Keyword, KeywordExtract, KeywordExtractConfig, KeywordExtractConfigBuilder};
+use super::{Keyword, KeywordExtract, KeywordExtractConfig, KeywordExtractConfigBuilder, KeywordSpan};
 use crate::FxHashMap as HashMap;
-use crate::Jieba;
+use crate::{Jieba, Tag};
 
 flate!(static DEFAULT_IDF: str from "src/data/idf.txt");
 
@@ -29,6 +29,86 @@ impl PartialOrd for HeapNode<'_> {
     }
 }
 
+/// Document frequencies counted in online mode, see [`TfIdf::enable_online`]
+#[derive(Debug, Default)]
+struct OnlineIdf {
+    doc_freq: HashMap<String, usize>,
+    /// Number of words by document frequency, to find the median IDF
+    df_words: BTreeMap<usize, usize>,
+    documents: usize,
+    max_documents: Option<usize>,
+    /// Words of the documents in the window, oldest first
+    window: VecDeque<Box<[String]>>,
+}
+
+impl OnlineIdf {
+    /// Counts `words` and updates their IDF in `idf_dict`
+    fn add(&mut self, words: &[String], idf_dict: &mut HashMap<String, f64>) {
+        for word in words {
+            let df = self.doc_freq.entry(word.clone()).or_insert(0);
+            Self::move_df(&mut self.df_words, *df, *df + 1);
+            *df += 1;
+            idf_dict.insert(word.clone(), relative_idf(*df));
+        }
+        self.documents += 1;
+    }
+
+    /// Uncounts `words` and updates their IDF in `idf_dict`
+    fn remove(&mut self, words: &[String], idf_dict: &mut HashMap<String, f64>) {
+        for word in words {
+            let Some(df) = self.doc_freq.get_mut(word.as_str()) else {
+                continue;
+            };
+            Self::move_df(&mut self.df_words, *df, *df - 1);
+            *df -= 1;
+            if *df == 0 {
+                self.doc_freq.remove(word.as_str());
+                idf_dict.remove(word.as_str());
+            } else {
+                idf_dict.insert(word.clone(), relative_idf(*df));
+            }
+        }
+        self.documents -= 1;
+    }
+
+    fn move_df(df_words: &mut BTreeMap<usize, usize>, from: usize, to: usize) {
+        if let Some(count) = df_words.get_mut(&from) {
+            *count -= 1;
+            if *count == 0 {
+                df_words.remove(&from);
+            }
+        }
+        if to > 0 {
+            *df_words.entry(to).or_insert(0) += 1;
+        }
+    }
+
+    /// Median IDF relative to [`OnlineIdf::idf_shift`], picked as in [`TfIdf::load_dict`]
+    fn median_idf(&self) -> f64 {
+        let words: usize = self.df_words.values().sum();
+        let mut seen = 0;
+        for (&df, &count) in &self.df_words {
+            // The IDF decreases with the document frequency
+            seen += count;
+            if seen > words / 2 {
+                return relative_idf(df);
+            }
+        }
+        relative_idf(0)
+    }
+
+    /// Part of the IDF shared by all the words, growing with the number of documents
+    fn idf_shift(&self) -> f64 {
+        ((1 + self.documents) as f64).ln()
+    }
+}
+
+/// Smoothed IDF of a word seen in `df` documents, without the [`OnlineIdf::idf_shift`]
+/// term, so that words not seen in a new document keep their entry
+fn relative_idf(df: usize) -> f64 {
+    1.0 - ((1 + df) as f64).ln()
+}
+
 /// TF-IDF keywords extraction
 ///
 /// Require `tfidf` feature to be enabled
@@ -36,6 +116,9 @@ impl PartialOrd for HeapNode<'_> {
 pub struct TfIdf {
     idf_dict: HashMap<String, f64>,
     median_idf: f64,
+    /// Added to the entries of `idf_dict` and to `median_idf` in online mode
+    idf_shift: f64,
+    online: Option<OnlineIdf>,
     config: KeywordExtractConfig,
 }
 
@@ -73,6 +156,8 @@ impl TfIdf {
         let mut instance = TfIdf {
             idf_dict: HashMap::default(),
             median_idf: 0.0,
+            idf_shift: 0.0,
+            online: None,
             config,
         };
         if let Some(dict) = opt_dict {
@@ -84,7 +169,7 @@ impl TfIdf {
     /// Merges entries from `dict` into the `idf_dict`.
     ///
     /// ```
-    /// use jieba_rs::{Jieba, KeywordExtract, Keyword, KeywordExtractConfig, TfIdf};
+    /// use jieba_rs::{Jieba, KeywordExtract, Keyword, KeywordExtractConfig, KeywordSpan, TfIdf};
     ///
     /// let jieba = Jieba::default();
     /// let mut init_idf = "生化学 13.900677652\n";
@@ -94,9 +179,24 @@ impl TfIdf {
     /// assert_eq!(
     ///     top_k,
     ///     vec![
-    ///         Keyword { keyword: "不是".to_string(), weight: 4.6335592173333335, tag: "c".to_string() },
-    ///         Keyword { keyword: "光化学".to_string(), weight: 4.6335592173333335, tag: "n".to_string() },
-    ///         Keyword { keyword: "生化学".to_string(), weight: 4.6335592173333335, tag: "n".to_string() }
+    ///         Keyword {
+    ///             keyword: "不是".to_string(),
+    ///             weight: 4.6335592173333335,
+    ///             tag: "c".to_string(),
+    ///             spans: vec![KeywordSpan { start: 3, end: 5, byte_start: 9, byte_end: 15 }],
+    ///         },
+    ///         Keyword {
+    ///             keyword: "光化学".to_string(),
+    ///             weight: 4.6335592173333335,
+    ///             tag: "n".to_string(),
+    ///             spans: vec![KeywordSpan { start: 5, end: 8, byte_start: 15, byte_end: 24 }],
+    ///         },
+    ///         Keyword {
+    ///             keyword: "生化学".to_string(),
+    ///             weight: 4.6335592173333335,
+    ///             tag: "n".to_string(),
+    ///             spans: vec![KeywordSpan { start: 0, end: 3, byte_start: 0, byte_end: 9 }],
+    ///         },
     ///     ],
     /// );
     ///
@@ -104,15 +204,22 @@ impl TfIdf {
     /// tfidf.load_dict(&mut init_idf.as_bytes()).unwrap();
     /// let new_top_k = tfidf.extract_keywords(&jieba, "生化学不是光化学的,", 3, vec![]);
     /// assert_eq!(
-    ///     new_top_k,
+    ///     new_top_k.iter().map(|k| (k.keyword.as_str(), k.weight, k.tag.as_str())).collect::<Vec<_>>(),
     ///     vec![
-    ///         Keyword { keyword: "不是".to_string(), weight: 33.041152263, tag: "c".to_string() },
-    ///         Keyword { keyword: "光化学".to_string(), weight: 33.041152263, tag: "n".to_string() },
-    ///         Keyword { keyword: "生化学".to_string(), weight: 4.6335592173333335, tag: "n".to_string() }
+    ///         ("不是", 33.041152263, "c"),
+    ///         ("光化学", 33.041152263, "n"),
+    ///         ("生化学", 4.6335592173333335, "n"),
     ///     ]
     /// );
     /// ```
     pub fn load_dict(&mut self, dict: &mut impl BufRead) -> io::Result<()> {
+        if self.online.take().is_some() {
+            for idf in self.idf_dict.values_mut() {
+                *idf += self.idf_shift;
+            }
+            self.idf_shift = 0.0;
+        }
+
         let mut buf = String::new();
         let mut idf_heap = BinaryHeap::new();
         while dict.read_line(&mut buf)? > 0 {
@@ -135,8 +242,125 @@ impl TfIdf {
             idf_heap.pop();
         }
 
-        self.median_idf = idf_heap.pop().unwrap().into_inner();
+        // A dictionary without entries, such as one written from an empty corpus, keeps the
+        // current median
+        if let Some(median) = idf_heap.pop() {
+            self.median_idf = median.into_inner();
+        }
+
+        Ok(())
+    }
+
+    /// Switches to online mode, where the IDF dictionary is computed from the document
+    /// frequencies of the documents added with [`TfIdf::add_document`].
+    ///
+    /// The current dictionary and counts are dropped. When `max_documents` is set, only the
+    /// latest `max_documents` documents are counted, older ones aging out as new ones are
+    /// added. IDF values are smoothed as in [`IdfBuilder`](crate::IdfBuilder), and words
+    /// missing from the counted documents get the median IDF. Loading a dictionary with
+    /// [`TfIdf::load_dict`] leaves online mode, keeping the computed entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jieba_rs::{Jieba, KeywordExtractConfig, TfIdf};
+    ///
+    /// let mut jieba = Jieba::empty();
+    /// jieba.add_word("区块链", Some(100), Some("n"));
+    /// jieba.add_word("技术", Some(100), Some("n"));
+    ///
+    /// let mut tfidf = TfIdf::new(None::<&mut std::io::Empty>, KeywordExtractConfig::default());
+    /// tfidf.enable_online(Some(2));
+    /// tfidf.add_document(&jieba, "区块链技术");
+    /// tfidf.add_document(&jieba, "区块链");
+    /// assert_eq!(tfidf.document_frequency("区块链"), 2);
+    ///
+    /// tfidf.add_document(&jieba, "技术");
+    /// assert_eq!(tfidf.document_count(), 2);
+    /// assert_eq!(tfidf.document_frequency("区块链"), 1);
+    ///
+    /// let mut snapshot = Vec::new();
+    /// tfidf.write_dict(&mut snapshot).unwrap();
+    /// let restored = TfIdf::new(Some(&mut snapshot.as_slice()), KeywordExtractConfig::default());
+    /// assert_eq!(restored.idf("区块链"), tfidf.idf("区块链"));
+    /// assert_eq!(restored.idf("技术"), tfidf.idf("技术"));
+    /// ```
+    pub fn enable_online(&mut self, max_documents: Option<usize>) {
+        self.idf_dict.clear();
+        let online = OnlineIdf {
+            max_documents,
+            ..OnlineIdf::default()
+        };
+        self.median_idf = online.median_idf();
+        self.idf_shift = online.idf_shift();
+        self.online = Some(online);
+    }
+
+    /// Whether the IDF dictionary is computed from added documents, see [`TfIdf::enable_online`]
+    pub fn is_online(&self) -> bool {
+        self.online.is_some()
+    }
+
+    /// Counts the words of `document` in online mode, segmented with the HMM setting and
+    /// filtered as keywords with the [`KeywordExtractConfig`], and updates the IDF of the
+    /// words of the added and aged out documents. Words containing whitespace are skipped, as
+    /// the dictionary format cannot hold them.
+    ///
+    /// Does nothing unless online mode was enabled with [`TfIdf::enable_online`], so that the
+    /// loaded dictionary is kept.
+    pub fn add_document(&mut self, jieba: &Jieba, document: &str) {
+        let Some(online) = self.online.as_mut() else {
+            return;
+        };
+        let mut words: Vec<&str> = jieba
+            .cut(document, self.config.use_hmm())
+            .into_iter()
+            .map(|token| token.word)
+            .filter(|word| self.config.is_keyword(word) && !word.contains(char::is_whitespace))
+            .collect();
+        words.sort_unstable();
+        words.dedup();
+        let words: Box<[String]> = words.into_iter().map(String::from).collect();
+
+        online.add(&words, &mut self.idf_dict);
+        if let Some(max_documents) = online.max_documents {
+            online.window.push_back(words);
+            while online.window.len() > max_documents
+                && let Some(old) = online.window.pop_front()
+            {
+                online.remove(&old, &mut self.idf_dict);
+            }
+        }
+        self.median_idf = online.median_idf();
+        self.idf_shift = online.idf_shift();
+    }
+
+    /// Number of documents counted in online mode
+    pub fn document_count(&self) -> usize {
+        self.online.as_ref().map_or(0, |online| online.documents)
+    }
 
+    /// Number of documents counted in online mode containing `word`
+    pub fn document_frequency(&self, word: &str) -> usize {
+        self.online
+            .as_ref()
+            .and_then(|online| online.doc_freq.get(word).copied())
+            .unwrap_or(0)
+    }
+
+    /// IDF of `word`, the median IDF if it is not in the dictionary
+    pub fn idf(&self, word: &str) -> f64 {
+        self.idf_dict.get(word).unwrap_or(&self.median_idf) + self.idf_shift
+    }
+
+    /// Writes the IDF dictionary as `word idf` lines, ordered by word, that
+    /// [`TfIdf::load_dict`] accepts.
+    pub fn write_dict<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut entries: Vec<(&String, &f64)> = self.idf_dict.iter().collect();
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        for (word, idf) in entries {
+            writeln!(writer, "{word} {}", idf + self.idf_shift)?;
+        }
         Ok(())
     }
 
@@ -209,15 +433,19 @@ impl KeywordExtract for TfIdf {
     /// ```
     fn extract_keywords(&self, jieba: &Jieba, sentence: &str, top_k: usize, allowed_pos: Vec<String>) -> Vec<Keyword> {
         let tags = jieba.tag(sentence, self.config.use_hmm());
+        self.extract_keywords_from_tags(&tags, top_k, allowed_pos)
+    }
+
+    fn extract_keywords_from_tags(&self, tags: &[Tag], top_k: usize, allowed_pos: Vec<String>) -> Vec<Keyword> {
         let mut allowed_pos_set = BTreeSet::new();
 
         for s in allowed_pos {
             allowed_pos_set.insert(s);
         }
 
-        let mut term_freq: HashMap<&str, u64> = HashMap::default();
+        let mut occurrences: HashMap<&str, Vec<KeywordSpan>> = HashMap::default();
         let mut word2tag: HashMap<&str, &str> = HashMap::default();
-        for t in &tags {
+        for t in tags {
             if !allowed_pos_set.is_empty() && !allowed_pos_set.contains(t.tag) {
                 continue;
             }
@@ -227,15 +455,15 @@ impl KeywordExtract for TfIdf {
             }
 
             word2tag.entry(t.word).or_insert(t.tag);
-            *term_freq.entry(t.word).or_insert(0) += 1;
+            occurrences.entry(t.word).or_default().push(KeywordSpan::from(t));
         }
 
-        let total: u64 = term_freq.values().sum();
+        let total: usize = occurrences.values().map(Vec::len).sum();
         let mut heap = BinaryHeap::new();
-        for (cnt, (k, tf)) in term_freq.iter().enumerate() {
-            let idf = self.idf_dict.get(*k).unwrap_or(&self.median_idf);
+        for (cnt, (k, spans)) in occurrences.iter().enumerate() {
+            let idf = self.idf(k);
             let node = HeapNode {
-                tfidf: OrderedFloat(*tf as f64 * idf / total as f64),
+                tfidf: OrderedFloat(spans.len() as f64 * idf / total as f64),
                 word: k,
             };
             heap.push(node);
@@ -251,6 +479,7 @@ impl KeywordExtract for TfIdf {
                     keyword: String::from(w.word),
                     weight: w.tfidf.into_inner(),
                     tag: String::from(*word2tag.get(w.word).unwrap_or(&"")),
+                    spans: occurrences.remove(w.word).unwrap_or_default(),
                 });
             }
         }
@@ -259,3 +488,72 @@ impl KeywordExtract for TfIdf {
         res
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IdfBuilder;
+
+    #[test]
+    fn test_online_idf() {
+        let mut jieba = Jieba::empty();
+        for word in ["天气", "很好", "下雨"] {
+            jieba.add_word(word, Some(100), Some("n"));
+        }
+        let config = KeywordExtractConfig::default();
+        let documents = ["天气很好", "下雨", "天气下雨", "很好"];
+
+        let mut tfidf = TfIdf::new(Some(&mut "天气 1.0\n".as_bytes()), config.clone());
+        tfidf.enable_online(Some(3));
+        assert!(tfidf.is_online());
+        for document in documents {
+            tfidf.add_document(&jieba, document);
+        }
+        assert_eq!(tfidf.document_count(), 3);
+        assert_eq!(tfidf.document_frequency("下雨"), 2);
+
+        let mut builder = IdfBuilder::new();
+        builder.add_documents(&jieba, &config, &documents[1..]);
+        for word in ["天气", "很好", "下雨"] {
+            assert!((tfidf.idf(word) - builder.idf(word)).abs() < 1e-12, "{word}");
+        }
+        // Median of the IDF of 天气, 很好 and 下雨, seen in 1, 1 and 2 documents
+        assert!((tfidf.idf("未见") - builder.idf("天气")).abs() < 1e-12);
+
+        let mut snapshot = Vec::new();
+        tfidf.write_dict(&mut snapshot).unwrap();
+        tfidf.load_dict(&mut "晴天 2.0\n".as_bytes()).unwrap();
+        assert!(!tfidf.is_online());
+        assert!((tfidf.idf("下雨") - builder.idf("下雨")).abs() < 1e-12);
+
+        let mut expected = Vec::new();
+        builder.write_dict(&mut expected).unwrap();
+        let restored = TfIdf::new(Some(&mut snapshot.as_slice()), config);
+        let mut written = Vec::new();
+        restored.write_dict(&mut written).unwrap();
+        assert_eq!(written.len(), expected.len());
+        assert!((restored.idf("下雨") - builder.idf("下雨")).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_add_document_offline() {
+        let mut jieba = Jieba::empty();
+        jieba.add_word("天气", Some(100), Some("n"));
+        jieba.add_word("New York", Some(100), Some("ns"));
+
+        let mut tfidf = TfIdf::new(Some(&mut "天气 1.0\n".as_bytes()), KeywordExtractConfig::default());
+        tfidf.add_document(&jieba, "天气");
+        assert!(!tfidf.is_online());
+        assert_eq!(tfidf.document_count(), 0);
+        assert_eq!(tfidf.idf("天气"), 1.0);
+
+        tfidf.enable_online(None);
+        tfidf.add_document(&jieba, "New York天气");
+        assert_eq!(tfidf.document_frequency("New York"), 0);
+        let mut snapshot = Vec::new();
+        tfidf.write_dict(&mut snapshot).unwrap();
+        let snapshot = String::from_utf8(snapshot).unwrap();
+        assert_eq!(snapshot.lines().count(), 1);
+        assert!(snapshot.starts_with("天气 "));
+    }
+}
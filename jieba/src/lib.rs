@@ -63,8 +63,11 @@
 //! ## Enabling Additional Features
 //!
 //! * `default-dict` feature enables embedded dictionary, this features is enabled by default
-//! * `tfidf` feature enables TF-IDF keywords extractor
+//! * `tfidf` feature enables TF-IDF keywords extractor and the IDF dictionary builder
 //! * `textrank` feature enables TextRank keywords extractor
+//! * `parallel` feature enables parallel corpus processing with rayon
+//! * `nfkc` feature enables Unicode NFKC normalization in [`Normalizer`]
+//! * `grapheme` feature enables emitting extended grapheme clusters outside of Han text
 //!
 //! ```toml
 //! [dependencies]
@@ -72,34 +75,68 @@
 //! ```
 //!
 
+use std::borrow::Cow;
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::io::BufRead;
 
 use cedarwood::Cedar;
+#[cfg(feature = "grapheme")]
+use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
 
 pub(crate) type FxHashMap<K, V> = HashMap<K, V, rustc_hash::FxBuildHasher>;
+pub(crate) type FxHashSet<K> = HashSet<K, rustc_hash::FxBuildHasher>;
 
+pub use crate::char_class::CharClasses;
+pub use crate::dict_builder::{DictBuilder, DictEntry};
+pub use crate::document::{Document, DocumentConfig};
+pub use crate::entities::{Entity, EntityKind};
 pub use crate::errors::Error;
+pub use crate::evaluation::{Evaluation, Evaluator, SentenceErrors};
 pub use crate::hmm::HmmModel;
+#[cfg(feature = "tfidf")]
+pub use crate::keywords::idf_builder::IdfBuilder;
 #[cfg(feature = "textrank")]
 pub use crate::keywords::textrank::TextRank;
 #[cfg(feature = "tfidf")]
 pub use crate::keywords::tfidf::TfIdf;
 #[cfg(any(feature = "tfidf", feature = "textrank"))]
-pub use crate::keywords::{DEFAULT_STOP_WORDS, Keyword, KeywordExtract, KeywordExtractConfig};
-
+pub use crate::keywords::{DEFAULT_STOP_WORDS, Keyword, KeywordExtract, KeywordExtractConfig, KeywordSpan};
+pub use crate::new_words::{NewWordContext, NewWordCounter, NewWordStats, NewWordsCut};
+pub use crate::normalize::{Normalized, Normalizer};
+pub use crate::pos_tag::{PosCategory, PosTag, Upos};
+pub use crate::posseg::PossegModel;
+pub use crate::quantity::{Quantity, QuantityKind};
+pub use crate::recognizer::{PatternKind, Recognized, Recognizer};
+pub use crate::sentence::{Sentence, SentenceSplitter};
+pub use crate::time_expr::{DateTime, TimeExpression, TimeUnit, TimeValue};
+pub use crate::word_discovery::{WordCandidate, WordDiscovery, WordDiscoveryConfig, WordDiscoveryConfigBuilder};
+
+mod char_class;
+mod dict_builder;
+mod document;
+mod entities;
 mod errors;
+mod evaluation;
 mod hmm;
 #[cfg(any(feature = "tfidf", feature = "textrank"))]
 mod keywords;
+mod new_words;
+mod normalize;
+mod pos_tag;
 mod posseg;
+mod quantity;
+mod recognizer;
+mod sentence;
 mod sparse_dag;
+mod time_expr;
+mod word_discovery;
 
 #[cfg(feature = "default-dict")]
 include_flate::flate!(static DEFAULT_DICT: str from "src/data/dict.txt");
 
+use posseg::PosGuess;
 use sparse_dag::StaticSparseDAG;
 
 thread_local! {
@@ -140,6 +177,113 @@ fn is_skip_cut_all(c: char) -> bool {
     !c.is_ascii_alphanumeric() && c != '+' && c != '#' && c != '\n'
 }
 
+/// Whether a dictionary word contains whitespace or punctuation that splits the input into
+/// several blocks, such as `New York` or `迈克尔·乔丹`. Letters, digits and `+#&._%-` are not
+/// separators, so that words such as `T恤` are segmented the same in every mode whether
+/// separator words are in the dictionary or not.
+#[inline]
+fn has_separator(word: &str) -> bool {
+    word.chars()
+        .any(|c| !c.is_alphanumeric() && !matches!(c, '+' | '#' | '&' | '.' | '_' | '%' | '-'))
+}
+
+/// Splits a dictionary line into its word, frequency and tag.
+///
+/// Fields are separated by tabs if the line is fully tab-separated, with a frequency of
+/// digits and no whitespace inside the frequency and tag, by whitespace otherwise. A word
+/// containing whitespace can also be quoted with `"`, with `\"` and `\\` escapes.
+fn parse_dict_line(line: &str) -> Option<(Cow<'_, str>, Option<&str>, Option<&str>)> {
+    let line = line.trim_end_matches(['\r', '\n']);
+    if line.contains('\t') {
+        let mut fields = line.split('\t').map(str::trim);
+        let word = fields.next().filter(|word| !word.is_empty());
+        let fields: Vec<&str> = fields.filter(|field| !field.is_empty()).collect();
+        let tab_separated = fields.len() <= 2
+            && fields.iter().all(|field| !field.contains(char::is_whitespace))
+            && fields
+                .first()
+                .is_none_or(|freq| freq.bytes().all(|b| b.is_ascii_digit()));
+        if tab_separated {
+            return word.map(|word| (Cow::Borrowed(word), fields.first().copied(), fields.get(1).copied()));
+        }
+    }
+
+    let line = line.trim_start();
+    if let Some(quoted) = line.strip_prefix('"') {
+        let mut word = String::new();
+        let mut chars = quoted.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    let mut fields = quoted[i + 1..].split_whitespace();
+                    return (!word.is_empty()).then(|| (Cow::Owned(word), fields.next(), fields.next()));
+                }
+                '\\' => word.extend(chars.next().map(|(_, c)| c)),
+                _ => word.push(c),
+            }
+        }
+        // Without a closing quote, the quote is part of the word
+    }
+    let mut fields = line.split_whitespace();
+    let word = fields.next()?;
+    Some((Cow::Borrowed(word), fields.next(), fields.next()))
+}
+
+/// Lower-case the letters of `text` whose simple lower-case mapping is a single char of the
+/// same UTF-8 length, so that byte offsets into the folded text are valid in `text`
+fn fold_case(text: &str) -> Cow<'_, str> {
+    let fold = |c: char| {
+        if c.is_ascii() {
+            return c.to_ascii_lowercase();
+        }
+        let mut lower = c.to_lowercase();
+        match (lower.next(), lower.next()) {
+            (Some(l), None) if l.len_utf8() == c.len_utf8() => l,
+            _ => c,
+        }
+    };
+    if text.chars().all(|c| fold(c) == c) {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(text.chars().map(fold).collect())
+    }
+}
+
+/// Kind of the token if it is one of the recognized `spans`
+fn recognized_kind<'a>(spans: &[(usize, usize, &'a PatternKind)], token: &Token) -> Option<&'a PatternKind> {
+    let i = spans.binary_search_by_key(&token.byte_start, |span| span.0).ok()?;
+    (spans[i].1 == token.byte_end).then_some(spans[i].2)
+}
+
+/// Normalized text of the tokens mapped to one original token by
+/// [`Normalized::map_tokens`], with the pattern it was recognized as
+fn normalized_word<'n, 'a>(
+    normalized: &'n Normalized<'_>,
+    tokens: &[Token<'_>],
+    spans: &[(usize, usize, &'a PatternKind)],
+) -> (&'n str, Option<&'a PatternKind>) {
+    let (first, last) = (&tokens[0], &tokens[tokens.len() - 1]);
+    let word = &normalized.as_str()[first.byte_start..last.byte_end];
+    let kind = match tokens {
+        [token] => recognized_kind(spans, token),
+        _ => None,
+    };
+    (word, kind)
+}
+
+/// Whether `byte` is inside an extended grapheme cluster of `sentence`
+#[cfg(feature = "grapheme")]
+fn splits_grapheme(sentence: &str, byte: usize) -> bool {
+    !GraphemeCursor::new(byte, sentence.len(), true)
+        .is_boundary(sentence, 0)
+        .unwrap_or(true)
+}
+
+#[cfg(not(feature = "grapheme"))]
+fn splits_grapheme(_sentence: &str, _byte: usize) -> bool {
+    false
+}
+
 #[inline]
 fn char_count(s: &str) -> usize {
     if s.len() >= 16 {
@@ -209,6 +353,8 @@ impl<'t, F: Fn(char) -> bool> Iterator for SplitByCharacterClass<'t, F> {
 pub(crate) enum SplitState<'t> {
     Unmatched(&'t str),
     Matched(&'t str),
+    /// Span recognized by the recognizer, emitted as a single token
+    Recognized(&'t str),
 }
 
 impl<'t> SplitState<'t> {
@@ -217,6 +363,7 @@ impl<'t> SplitState<'t> {
         match self {
             SplitState::Unmatched(t) => t,
             SplitState::Matched(t) => t,
+            SplitState::Recognized(t) => t,
         }
     }
 
@@ -266,6 +413,78 @@ pub struct Tag<'a> {
     pub byte_end: usize,
 }
 
+impl Tag<'_> {
+    /// Typed POS tag of the word
+    pub fn pos(&self) -> PosTag {
+        PosTag::new(self.tag)
+    }
+}
+
+impl<'a> Tag<'a> {
+    /// Tags of a sequence of `(word, tag)` pairs, such as the output of another segmenter,
+    /// with the offsets of the words in their concatenation
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use jieba_rs::Tag;
+    ///
+    /// let tags = Tag::from_pairs([("北京", "ns"), ("欢迎", "v")]);
+    /// assert_eq!((tags[1].start, tags[1].end), (2, 4));
+    /// assert_eq!((tags[1].byte_start, tags[1].byte_end), (6, 12));
+    /// ```
+    pub fn from_pairs<I: IntoIterator<Item = (&'a str, &'a str)>>(pairs: I) -> Vec<Tag<'a>> {
+        let mut start = 0;
+        let mut byte_start = 0;
+        pairs
+            .into_iter()
+            .map(|(word, tag)| {
+                let end = start + char_count(word);
+                let byte_end = byte_start + word.len();
+                let tag = Tag {
+                    word,
+                    tag,
+                    start,
+                    end,
+                    byte_start,
+                    byte_end,
+                };
+                start = end;
+                byte_start = byte_end;
+                tag
+            })
+            .collect()
+    }
+}
+
+/// Where the tag of a word comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TagSource {
+    /// Tag of the word in the dictionary
+    Dictionary,
+    /// Label of the [`PatternKind`] of a span recognized by the [`Recognizer`]
+    Pattern,
+    /// Tag guessed by the POS tagging HMM
+    Hmm,
+    /// `eng` or `m` for words with ASCII letters or digits
+    Heuristic,
+    /// `x` for words no other source could tag
+    Fallback,
+}
+
+/// A tagged word with the source of its tag
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagDetail<'a> {
+    /// Tagged word
+    pub tag: Tag<'a>,
+    /// Source of the tag
+    pub source: TagSource,
+    /// For tags guessed by the POS tagging HMM, the Viterbi log-probability margin of the
+    /// tag over the best other tag at the end of the word, `f64::INFINITY` if no other tag
+    /// is possible. `None` for other sources.
+    pub margin: Option<f64>,
+}
+
 #[derive(Debug, Clone)]
 struct Record {
     freq: usize,
@@ -295,8 +514,23 @@ impl Record {
 pub struct Jieba {
     records: Vec<Record>,
     cedar: Cedar,
+    /// Case-folded forms of the dictionary words containing upper-case letters
+    folded_cedar: Cedar,
+    case_insensitive: bool,
     total: usize,
     hmm_model: Option<HmmModel>,
+    posseg_model: Option<PossegModel>,
+    normalizer: Option<Normalizer>,
+    recognizer: Option<Recognizer>,
+    char_classes: CharClasses,
+    /// Dictionary words containing separators, see `has_separator`, with the value 0, and
+    /// their case-folded forms with the value 1
+    separator_cedar: Cedar,
+    /// Whether `separator_cedar` is not empty
+    separator_words: bool,
+    #[cfg(feature = "grapheme")]
+    grapheme_clusters: bool,
+    merge_whitespace: bool,
 }
 
 impl fmt::Debug for Jieba {
@@ -321,8 +555,19 @@ impl Jieba {
         Jieba {
             records: Vec::new(),
             cedar: Cedar::new(),
+            folded_cedar: Cedar::new(),
+            case_insensitive: false,
             total: 0,
             hmm_model: None,
+            posseg_model: None,
+            normalizer: None,
+            recognizer: None,
+            char_classes: CharClasses::default(),
+            separator_cedar: Cedar::new(),
+            separator_words: false,
+            #[cfg(feature = "grapheme")]
+            grapheme_clusters: false,
+            merge_whitespace: false,
         }
     }
 
@@ -376,7 +621,7 @@ impl Jieba {
     /// When set, the custom model is used instead of the compile-time embedded model
     /// for HMM-based segmentation of out-of-vocabulary words.
     ///
-    /// The model can be trained using `scripts/train_hmm.py`.
+    /// The model can be trained with [`HmmModel::train`] or `scripts/train_hmm.py`.
     ///
     /// ## Example
     ///
@@ -394,6 +639,207 @@ impl Jieba {
         self.hmm_model = Some(model);
     }
 
+    /// Set a custom POS tagging model.
+    ///
+    /// When set, the custom model is used instead of the compile-time embedded
+    /// model to guess the tags of out-of-vocabulary words in [`tag`](#method.tag).
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use std::io::BufReader;
+    /// use std::fs::File;
+    /// use jieba_rs::{Jieba, PossegModel};
+    ///
+    /// let mut jieba = Jieba::new();
+    /// let mut f = BufReader::new(File::open("my_posseg.txt").unwrap());
+    /// let model = PossegModel::load(&mut f).unwrap();
+    /// jieba.set_posseg_model(model);
+    /// ```
+    pub fn set_posseg_model(&mut self, model: PossegModel) {
+        self.posseg_model = Some(model);
+    }
+
+    /// Set a normalizer applied to the input text before segmentation.
+    ///
+    /// The text is segmented and tagged in its normalized form, so that full-width or
+    /// traditional variants match the dictionary, but the returned tokens are slices of
+    /// the original input with offsets into it.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use jieba_rs::{Jieba, Normalizer};
+    ///
+    /// let mut jieba = Jieba::empty();
+    /// jieba.add_word("ABC", Some(100), None);
+    /// jieba.add_word("资料", Some(100), None);
+    ///
+    /// let mut normalizer = Normalizer::new().fold_width(true);
+    /// normalizer.add_conversion("資", "资");
+    /// jieba.set_normalizer(normalizer);
+    ///
+    /// let words: Vec<&str> = jieba.cut("ＡＢＣ資料", false).into_iter().map(|t| t.word).collect();
+    /// assert_eq!(words, vec!["ＡＢＣ", "資料"]);
+    /// ```
+    pub fn set_normalizer(&mut self, normalizer: Normalizer) {
+        self.normalizer = Some(normalizer);
+    }
+
+    /// Set a recognizer for spans emitted as single tokens, such as URLs, emails and hashtags.
+    ///
+    /// Recognized spans are tagged with the label of their [`PatternKind`], the text around
+    /// them is segmented as usual.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use jieba_rs::{Jieba, Recognizer, TagSource};
+    ///
+    /// let mut jieba = Jieba::empty();
+    /// jieba.set_recognizer(Recognizer::builtin());
+    ///
+    /// let words: Vec<&str> = jieba.cut("@张三 看 https://example.com/a?b=1", false).into_iter().map(|t| t.word).collect();
+    /// assert_eq!(words, vec!["@张三", " ", "看", " ", "https://example.com/a?b=1"]);
+    ///
+    /// let tags = jieba.tag_detail("看 https://example.com", false);
+    /// assert_eq!(tags[2].tag.tag, "url");
+    /// assert_eq!(tags[2].source, TagSource::Pattern);
+    /// ```
+    pub fn set_recognizer(&mut self, recognizer: Recognizer) {
+        self.recognizer = Some(recognizer);
+    }
+
+    /// Byte spans of the text recognized by the recognizer, with their kinds
+    fn recognized_spans(&self, text: &str) -> Vec<(usize, usize, &PatternKind)> {
+        match &self.recognizer {
+            Some(recognizer) => recognizer.spans(text),
+            None => Vec::new(),
+        }
+    }
+
+    /// Set the character classes deciding which runs of the input text are segmented.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use jieba_rs::{CharClasses, Jieba};
+    ///
+    /// let mut jieba = Jieba::empty();
+    /// jieba.add_word("迈克尔·乔丹", Some(100), None);
+    /// jieba.set_char_classes(CharClasses::new().han_default(|c| CharClasses::default_han_default(c) || c == '·'));
+    ///
+    /// let words: Vec<&str> = jieba.cut("迈克尔·乔丹", false).into_iter().map(|t| t.word).collect();
+    /// assert_eq!(words, vec!["迈克尔·乔丹"]);
+    /// ```
+    pub fn set_char_classes(&mut self, classes: CharClasses) {
+        self.char_classes = classes;
+    }
+
+    /// The character classes deciding which runs of the input text are segmented
+    pub fn char_classes(&self) -> &CharClasses {
+        &self.char_classes
+    }
+
+    /// Emit extended grapheme clusters rather than single chars outside of Han blocks.
+    ///
+    /// Emoji sequences, flags and characters with combining marks or variation selectors
+    /// are then kept in one token. Tokens split inside a grapheme cluster are merged.
+    ///
+    /// Requires `grapheme` feature to be enabled.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use jieba_rs::Jieba;
+    ///
+    /// let mut jieba = Jieba::empty();
+    /// jieba.set_grapheme_clusters(true);
+    /// let words: Vec<&str> = jieba.cut("好👍🏽🇨🇳", false).into_iter().map(|t| t.word).collect();
+    /// assert_eq!(words, vec!["好", "👍🏽", "🇨🇳"]);
+    /// ```
+    #[cfg(feature = "grapheme")]
+    pub fn set_grapheme_clusters(&mut self, enabled: bool) {
+        self.grapheme_clusters = enabled;
+    }
+
+    /// Emit runs of whitespace as a single token rather than one token per char.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use jieba_rs::Jieba;
+    ///
+    /// let mut jieba = Jieba::empty();
+    /// jieba.set_merge_whitespace(true);
+    /// let words: Vec<&str> = jieba.cut("中 \t 文", false).into_iter().map(|t| t.word).collect();
+    /// assert_eq!(words, vec!["中", " \t ", "文"]);
+    /// ```
+    pub fn set_merge_whitespace(&mut self, enabled: bool) {
+        self.merge_whitespace = enabled;
+    }
+
+    fn grapheme_clusters(&self) -> bool {
+        #[cfg(feature = "grapheme")]
+        {
+            self.grapheme_clusters
+        }
+        #[cfg(not(feature = "grapheme"))]
+        {
+            false
+        }
+    }
+
+    /// Byte length of the token at the start of `text`, outside of Han blocks: a run of
+    /// whitespace, an extended grapheme cluster or a char depending on the options
+    fn unit_len(&self, text: &str) -> usize {
+        if self.merge_whitespace && text.starts_with(char::is_whitespace) {
+            return text.find(|c: char| !c.is_whitespace()).unwrap_or(text.len());
+        }
+        #[cfg(feature = "grapheme")]
+        if self.grapheme_clusters
+            && let Some(grapheme) = text.graphemes(true).next()
+        {
+            return grapheme.len();
+        }
+        text.chars().next().map_or(0, char::len_utf8)
+    }
+
+    /// Merge the tokens split inside a grapheme cluster or inside a run of whitespace,
+    /// depending on the options. Returns the new index of each token if any option is set.
+    fn merge_units<'a>(&self, sentence: &'a str, tokens: &mut Vec<Token<'a>>) -> Option<Vec<usize>> {
+        let graphemes = self.grapheme_clusters();
+        if !graphemes && !self.merge_whitespace {
+            return None;
+        }
+        let is_whitespace = |word: &str| word.chars().all(char::is_whitespace);
+        let mut index = Vec::with_capacity(tokens.len());
+        let mut merged: Vec<Token<'a>> = Vec::with_capacity(tokens.len());
+        for token in tokens.drain(..) {
+            match merged.last_mut() {
+                Some(last)
+                    if last.byte_end == token.byte_start
+                        && ((graphemes && splits_grapheme(sentence, token.byte_start))
+                            || (self.merge_whitespace && is_whitespace(last.word) && is_whitespace(token.word))) =>
+                {
+                    last.word = &sentence[last.byte_start..token.byte_end];
+                    last.end = token.end;
+                    last.byte_end = token.byte_end;
+                }
+                _ => merged.push(token),
+            }
+            index.push(merged.len() - 1);
+        }
+        *tokens = merged;
+        Some(index)
+    }
+
+    /// The input text normalized by the normalizer, if it changes it
+    fn normalize<'a>(&self, sentence: &'a str) -> Option<Normalized<'a>> {
+        let normalized = self.normalizer.as_ref()?.normalize(sentence);
+        (normalized.as_str() != sentence).then_some(normalized)
+    }
+
     /// Clears all data
     ///
     /// This method performs the following actions:
@@ -418,6 +864,9 @@ impl Jieba {
     pub fn clear(&mut self) {
         self.records.clear();
         self.cedar = Cedar::new();
+        self.folded_cedar = Cedar::new();
+        self.separator_cedar = Cedar::new();
+        self.separator_words = false;
         self.total = 0;
     }
 
@@ -446,6 +895,8 @@ impl Jieba {
                 self.records.push(Record::new(freq, tag.into()));
 
                 self.cedar.update(word, word_id);
+                self.insert_folded(word, word_id);
+                self.insert_separator(word);
                 self.total += freq;
             }
         };
@@ -463,7 +914,71 @@ impl Jieba {
     ///
     /// * `bool` - Whether the word exists in the dictionary.
     pub fn has_word(&self, word: &str) -> bool {
-        self.cedar.exact_match_search(word).is_some()
+        self.word_id(word).is_some()
+    }
+
+    /// Match Latin, Greek, Cyrillic and other cased letters case-insensitively in the dictionary.
+    ///
+    /// Words are looked up with their letters lower-cased, in the segmentation DAG, in the
+    /// search mode n-grams, in [`Jieba::has_word`] and for the dictionary tags. The returned
+    /// tokens are still slices of the original text. When several dictionary words only
+    /// differ by case, the one with the same case as the text is preferred, then the
+    /// lower-case one.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use jieba_rs::Jieba;
+    ///
+    /// let mut jieba = Jieba::empty();
+    /// jieba.add_word("iPhone", Some(100), Some("nz"));
+    /// jieba.add_word("手机", Some(100), Some("n"));
+    /// assert!(!jieba.has_word("IPHONE"));
+    ///
+    /// jieba.set_case_insensitive(true);
+    /// assert!(jieba.has_word("IPHONE"));
+    /// let words: Vec<&str> = jieba.cut("IPHONE手机", false).into_iter().map(|t| t.word).collect();
+    /// assert_eq!(words, vec!["IPHONE", "手机"]);
+    /// ```
+    pub fn set_case_insensitive(&mut self, enabled: bool) {
+        self.case_insensitive = enabled;
+    }
+
+    /// Index the case-folded form of `word`, if it differs from the word
+    fn insert_folded(&mut self, word: &str, word_id: i32) {
+        if let Cow::Owned(folded) = fold_case(word)
+            && self.folded_cedar.exact_match_search(&folded).is_none()
+        {
+            self.folded_cedar.update(&folded, word_id);
+        }
+    }
+
+    /// Index `word` and its case-folded form among the separator words, if it has separators
+    fn insert_separator(&mut self, word: &str) {
+        if !has_separator(word) {
+            return;
+        }
+        self.separator_cedar.update(word, 0);
+        if let Cow::Owned(folded) = fold_case(word)
+            && self.separator_cedar.exact_match_search(&folded).is_none()
+        {
+            self.separator_cedar.update(&folded, 1);
+        }
+        self.separator_words = true;
+    }
+
+    /// Id of a dictionary word, ignoring case with case-insensitive matching, in which case
+    /// the word with the exact case is preferred
+    fn word_id(&self, word: &str) -> Option<i32> {
+        let exact = self.cedar.exact_match_search(word);
+        if !self.case_insensitive || exact.is_some() {
+            return exact.map(|(word_id, _, _)| word_id);
+        }
+        let folded = fold_case(word);
+        self.cedar
+            .exact_match_search(&folded)
+            .or_else(|| self.folded_cedar.exact_match_search(&folded))
+            .map(|(word_id, _, _)| word_id)
     }
 
     /// Loads a dictionary by adding entries to the existing dictionary rather than resetting it.
@@ -471,6 +986,10 @@ impl Jieba {
     /// This function reads from a `BufRead` source, parsing each line as a dictionary entry. Each entry
     /// is expected to contain a word, its frequency, and optionally a tag.
     ///
+    /// Fields are separated by whitespace, or by tabs if the line contains any. Words with whitespace
+    /// can be written either in a tab-separated line or quoted: `"New York" 100 ns`. Words containing
+    /// spaces or punctuation are matched across the non-Han characters they contain.
+    ///
     /// # Type Parameters
     ///
     /// * `R`: A type that implements the `BufRead` trait, used for reading lines from the dictionary.
@@ -497,10 +1016,8 @@ impl Jieba {
         while dict.read_line(&mut buf)? > 0 {
             {
                 line_no += 1;
-                let mut iter = buf.split_whitespace();
-                if let Some(word) = iter.next() {
-                    let freq = iter
-                        .next()
+                if let Some((word, freq, tag)) = parse_dict_line(&buf) {
+                    let freq = freq
                         .map(|x| {
                             x.parse::<usize>().map_err(|e| {
                                 Error::InvalidDictEntry(format!(
@@ -509,16 +1026,18 @@ impl Jieba {
                             })
                         })
                         .unwrap_or(Ok(0))?;
-                    let tag = iter.next().unwrap_or("");
+                    let tag = tag.unwrap_or("");
 
-                    match self.cedar.exact_match_search(word) {
+                    match self.cedar.exact_match_search(&word) {
                         Some((word_id, _, _)) => {
                             self.records[word_id as usize].set_freq(freq);
                         }
                         None => {
                             let word_id = self.records.len() as i32;
                             self.records.push(Record::new(freq, tag.into()));
-                            self.cedar.update(word, word_id);
+                            self.cedar.update(&word, word_id);
+                            self.insert_folded(&word, word_id);
+                            self.insert_separator(&word);
                         }
                     };
                 }
@@ -531,8 +1050,8 @@ impl Jieba {
     }
 
     fn get_word_freq(&self, word: &str, default: usize) -> usize {
-        match self.cedar.exact_match_search(word) {
-            Some((word_id, _, _)) => self.records[word_id as usize].freq,
+        match self.word_id(word) {
+            Some(word_id) => self.records[word_id as usize].freq,
             _ => default,
         }
     }
@@ -540,9 +1059,12 @@ impl Jieba {
     /// Suggest word frequency to force the characters in a word to be joined or split.
     pub fn suggest_freq(&self, segment: &str) -> usize {
         let logtotal = (self.total as f64).ln();
-        let logfreq = self.cut(segment, false).iter().fold(0f64, |freq, token| {
-            freq + (self.get_word_freq(token.word, 1) as f64).ln() - logtotal
-        });
+        let logfreq = self
+            .cut_raw(segment, &self.recognized_spans(segment), false, false, None)
+            .iter()
+            .fold(0f64, |freq, token| {
+                freq + (self.get_word_freq(token.word, 1) as f64).ln() - logtotal
+            });
         std::cmp::max((logfreq + logtotal).exp() as usize + 1, self.get_word_freq(segment, 1))
     }
 
@@ -584,6 +1106,9 @@ impl Jieba {
     }
 
     fn dag(&self, sentence: &str, dag: &mut StaticSparseDAG) {
+        if self.case_insensitive {
+            return self.dag_case_insensitive(sentence, dag);
+        }
         for (byte_start, _) in sentence.char_indices() {
             dag.start(byte_start);
             let haystack = &sentence[byte_start..];
@@ -596,6 +1121,36 @@ impl Jieba {
         }
     }
 
+    /// Like `dag`, matching the case-folded sentence with both the dictionary words and
+    /// their case-folded forms. Folding keeps byte offsets, see `fold_case`.
+    fn dag_case_insensitive(&self, sentence: &str, dag: &mut StaticSparseDAG) {
+        let folded = fold_case(sentence);
+        let mut edges: Vec<(usize, i32)> = Vec::new();
+        for (byte_start, _) in folded.char_indices() {
+            dag.start(byte_start);
+            let haystack = &folded[byte_start..];
+
+            // Folding keeps the byte offsets, words with the exact case are preferred
+            edges.clear();
+            let exact = self.cedar.common_prefix_iter(&sentence[byte_start..]);
+            let folded_words = self
+                .cedar
+                .common_prefix_iter(haystack)
+                .chain(self.folded_cedar.common_prefix_iter(haystack));
+            for (word_id, end_index) in exact.chain(folded_words) {
+                if !edges.iter().any(|&(end, _)| end == end_index) {
+                    edges.push((end_index, word_id));
+                }
+            }
+            edges.sort_unstable_by_key(|&(end, _)| end);
+            for &(end_index, word_id) in &edges {
+                dag.insert(end_index + byte_start + 1, word_id);
+            }
+
+            dag.commit();
+        }
+    }
+
     /// Emits `Token`s directly with unicode positions for cut_all,
     /// avoiding the need for a separate byte-to-unicode lookup table.
     fn cut_all_tokens<'a>(&self, block: &'a str, base: usize, block_unicode_start: usize, tokens: &mut Vec<Token<'a>>) {
@@ -668,13 +1223,29 @@ impl Jieba {
         route.clear();
     }
 
+    /// Cut an out-of-vocabulary run with the HMM.
+    ///
+    /// If `new_words` is given, the indices into `words` of the Han words of at least two
+    /// chars discovered by the HMM that are not in the dictionary are appended to it.
     #[inline]
-    fn hmm_cut<'a>(&self, word: &'a str, words: &mut Vec<&'a str>, hmm_context: &mut hmm::HmmContext) {
+    fn hmm_cut<'a>(
+        &self,
+        word: &'a str,
+        words: &mut Vec<&'a str>,
+        hmm_context: &mut hmm::HmmContext,
+        new_words: Option<&mut Vec<usize>>,
+    ) {
+        let first = words.len();
         if let Some(ref model) = self.hmm_model {
             hmm::cut_with_allocated_memory(word, words, model, hmm_context);
         } else {
             hmm::cut_with_allocated_memory(word, words, &hmm::builtin_hmm(), hmm_context);
         }
+        if let Some(new_words) = new_words {
+            new_words.extend((first..words.len()).filter(|&i| {
+                char_count(words[i]) >= 2 && words[i].chars().all(hmm::is_hmm_han) && self.word_id(words[i]).is_none()
+            }));
+        }
     }
 
     #[allow(non_snake_case, clippy::too_many_arguments)]
@@ -685,6 +1256,7 @@ impl Jieba {
         route: &mut Vec<(f64, usize)>,
         dag: &mut StaticSparseDAG,
         hmm_context: &mut hmm::HmmContext,
+        mut new_words: Option<&mut Vec<usize>>,
     ) {
         self.dag(sentence, dag);
         self.calc(sentence, dag, route);
@@ -704,8 +1276,8 @@ impl Jieba {
                     let word = &sentence[byte_start..byte_end];
                     if word.chars().nth(1).is_none() {
                         words.push(word);
-                    } else if self.cedar.exact_match_search(word).is_none() {
-                        self.hmm_cut(word, words, hmm_context);
+                    } else if self.word_id(word).is_none() {
+                        self.hmm_cut(word, words, hmm_context, new_words.as_deref_mut());
                     } else {
                         let mut word_indices = word.char_indices().map(|x| x.0).peekable();
                         while let Some(byte_start) = word_indices.next() {
@@ -729,8 +1301,8 @@ impl Jieba {
 
             if word.chars().nth(1).is_none() {
                 words.push(word);
-            } else if self.cedar.exact_match_search(word).is_none() {
-                self.hmm_cut(word, words, hmm_context);
+            } else if self.word_id(word).is_none() {
+                self.hmm_cut(word, words, hmm_context, new_words);
             } else {
                 let mut word_indices = word.char_indices().map(|x| x.0).peekable();
                 while let Some(byte_start) = word_indices.next() {
@@ -747,6 +1319,59 @@ impl Jieba {
         route.clear();
     }
 
+    /// Like `cut_dag_hmm`, but out-of-vocabulary runs are segmented and tagged together
+    /// by the POS tagging model, see `posseg::cut_detail`, with the tag margins if `margins`
+    /// is true. Words not tagged by the model are paired with `None`.
+    fn cut_dag_posseg<'a, 'b>(
+        &self,
+        sentence: &'a str,
+        model: &'b PossegModel,
+        margins: bool,
+        words: &mut Vec<(&'a str, Option<PosGuess<'b>>)>,
+        route: &mut Vec<(f64, usize)>,
+        dag: &mut StaticSparseDAG,
+    ) {
+        self.dag(sentence, dag);
+        self.calc(sentence, dag, route);
+
+        let cut_run = |run: &'a str, words: &mut Vec<(&'a str, Option<PosGuess<'b>>)>| {
+            if run.chars().nth(1).is_none() {
+                words.push((run, None));
+            } else if self.word_id(run).is_none() {
+                posseg::cut_detail(model, run, margins, words);
+            } else {
+                let mut indices = run.char_indices().map(|x| x.0).peekable();
+                while let Some(byte_start) = indices.next() {
+                    let byte_end = indices.peek().copied().unwrap_or(run.len());
+                    words.push((&run[byte_start..byte_end], None));
+                }
+            }
+        };
+
+        let mut x = 0;
+        let mut left: Option<usize> = None;
+        while x < sentence.len() {
+            let y = route[x].1;
+            if sentence[x..y].chars().nth(1).is_none() {
+                if left.is_none() {
+                    left = Some(x);
+                }
+            } else {
+                if let Some(byte_start) = left.take() {
+                    cut_run(&sentence[byte_start..x], words);
+                }
+                words.push((&sentence[x..y], None));
+            }
+            x = y;
+        }
+        if let Some(byte_start) = left {
+            cut_run(&sentence[byte_start..], words);
+        }
+
+        dag.clear();
+        route.clear();
+    }
+
     /// Create a Token with incrementally tracked unicode offset.
     /// Returns the updated unicode_offset (past the end of this token).
     #[inline]
@@ -768,10 +1393,141 @@ impl Jieba {
         }
     }
 
+    /// Split the input text into the `recognized` spans of the recognizer, runs of characters
+    /// of the `classify` class and runs of other characters.
+    fn split_blocks<'a: 'c, 'c, F: Fn(char) -> bool + Copy + 'c>(
+        &self,
+        sentence: &'a str,
+        recognized: &[(usize, usize, &PatternKind)],
+        classify: F,
+    ) -> Box<dyn Iterator<Item = SplitState<'a>> + 'c> {
+        if recognized.is_empty() {
+            return self.split_class_blocks(sentence, classify);
+        }
+
+        let mut blocks = Vec::new();
+        let mut pos = 0;
+        for &(start, end, _) in recognized {
+            blocks.extend(self.split_class_blocks(&sentence[pos..start], classify));
+            blocks.push(SplitState::Recognized(&sentence[start..end]));
+            pos = end;
+        }
+        blocks.extend(self.split_class_blocks(&sentence[pos..], classify));
+        Box::new(blocks.into_iter())
+    }
+
+    /// Split the input text into runs of characters of the `classify` class and runs of other
+    /// characters. Runs joined by dictionary words containing other characters, such as
+    /// `New York` or `迈克尔·乔丹`, are merged into a single run of the class.
+    fn split_class_blocks<'a: 'c, 'c, F: Fn(char) -> bool + Copy + 'c>(
+        &self,
+        sentence: &'a str,
+        classify: F,
+    ) -> Box<dyn Iterator<Item = SplitState<'a>> + 'c> {
+        let spans = if self.separator_words {
+            self.separator_spans(sentence, classify)
+        } else {
+            Vec::new()
+        };
+        if spans.is_empty() {
+            return Box::new(SplitByCharacterClass::new(sentence, classify));
+        }
+
+        let mut blocks = Vec::new();
+        let mut pos = 0;
+        for (start, end) in spans {
+            blocks.extend(SplitByCharacterClass::new(&sentence[pos..start], classify));
+            blocks.push(SplitState::Matched(&sentence[start..end]));
+            pos = end;
+        }
+        blocks.extend(SplitByCharacterClass::new(&sentence[pos..], classify));
+        Box::new(blocks.into_iter())
+    }
+
+    /// Byte spans of the dictionary words containing separators and characters outside of
+    /// the `classify` class, widened to the runs of the class around them and merged when
+    /// overlapping
+    fn separator_spans<F: Fn(char) -> bool>(&self, sentence: &str, classify: F) -> Vec<(usize, usize)> {
+        let mut spans: Vec<(usize, usize)> = Vec::new();
+        let folded = if self.case_insensitive {
+            fold_case(sentence)
+        } else {
+            Cow::Borrowed(sentence)
+        };
+        for (start, _) in sentence.char_indices() {
+            let rest = &sentence[start..];
+            let longest = self
+                .separator_cedar
+                .common_prefix_iter(&folded[start..])
+                .filter(|&(folded, _)| folded == 0 || self.case_insensitive)
+                .map(|(_, end)| end + 1)
+                .filter(|&len| !rest[..len].chars().all(&classify))
+                .max();
+            let Some(len) = longest else {
+                continue;
+            };
+            let end = start + len;
+            let start = sentence[..start]
+                .char_indices()
+                .rev()
+                .take_while(|&(_, c)| classify(c))
+                .last()
+                .map_or(start, |(i, _)| i);
+            let end = end
+                + sentence[end..]
+                    .char_indices()
+                    .find(|&(_, c)| !classify(c))
+                    .map_or(sentence.len() - end, |(i, _)| i);
+            match spans.last_mut() {
+                Some(last) if last.1 >= start => last.1 = last.1.max(end),
+                _ => spans.push((start, end)),
+            }
+        }
+        spans
+    }
+
+    fn cut_internal<'a>(
+        &self,
+        sentence: &'a str,
+        cut_all: bool,
+        hmm: bool,
+        new_words: Option<&mut Vec<usize>>,
+    ) -> Vec<Token<'a>> {
+        let Some(normalized) = self.normalize(sentence) else {
+            return self.cut_raw(sentence, &self.recognized_spans(sentence), cut_all, hmm, new_words);
+        };
+        let mut new_words = new_words;
+        let recognized = self.recognized_spans(normalized.as_str());
+        let tokens = self.cut_raw(normalized.as_str(), &recognized, cut_all, hmm, new_words.as_deref_mut());
+        let mapped = normalized.map_tokens(&tokens);
+        if mapped.len() < tokens.len()
+            && let Some(new_words) = new_words
+        {
+            let mut index = Vec::with_capacity(tokens.len());
+            for (i, (_, range)) in mapped.iter().enumerate() {
+                index.extend(range.clone().map(|_| i));
+            }
+            for idx in new_words.iter_mut() {
+                *idx = index[*idx];
+            }
+            new_words.dedup();
+        }
+        mapped.into_iter().map(|(token, _)| token).collect()
+    }
+
+    /// Cut the input text without normalization, `recognized` being the spans of the
+    /// recognizer in it
     #[allow(non_snake_case)]
-    fn cut_internal<'a>(&self, sentence: &'a str, cut_all: bool, hmm: bool) -> Vec<Token<'a>> {
+    fn cut_raw<'a>(
+        &self,
+        sentence: &'a str,
+        recognized: &[(usize, usize, &PatternKind)],
+        cut_all: bool,
+        hmm: bool,
+        mut new_words: Option<&mut Vec<usize>>,
+    ) -> Vec<Token<'a>> {
         if cut_all {
-            return self.cut_all_toplevel(sentence);
+            return self.cut_all_toplevel(sentence, recognized);
         }
         let base = sentence.as_ptr() as usize;
         let mut unicode_offset = 0;
@@ -780,7 +1536,7 @@ impl Jieba {
         let mut str_words = Vec::with_capacity(heuristic_capacity);
         let mut tokens = Vec::with_capacity(heuristic_capacity);
 
-        let splitter = SplitByCharacterClass::new(sentence, is_han_default);
+        let splitter = self.split_blocks(sentence, recognized, |c| self.char_classes.is_han_default(c));
         let mut route = Vec::with_capacity(heuristic_capacity);
         let mut dag = StaticSparseDAG::with_size_hint(heuristic_capacity);
 
@@ -792,10 +1548,24 @@ impl Jieba {
 
                     str_words.clear();
                     if hmm {
+                        let block_token_start = tokens.len();
+                        let first_new_word = new_words.as_ref().map_or(0, |v| v.len());
                         HMM_CONTEXT.with(|ctx| {
                             let mut hmm_context = ctx.borrow_mut();
-                            self.cut_dag_hmm(block, &mut str_words, &mut route, &mut dag, &mut hmm_context);
+                            self.cut_dag_hmm(
+                                block,
+                                &mut str_words,
+                                &mut route,
+                                &mut dag,
+                                &mut hmm_context,
+                                new_words.as_deref_mut(),
+                            );
                         });
+                        if let Some(new_words) = new_words.as_deref_mut() {
+                            for idx in &mut new_words[first_new_word..] {
+                                *idx += block_token_start;
+                            }
+                        }
                     } else {
                         self.cut_dag_no_hmm(block, &mut str_words, &mut route, &mut dag);
                     }
@@ -806,40 +1576,55 @@ impl Jieba {
                 SplitState::Unmatched(_) => {
                     let block = state.as_str();
                     assert!(!block.is_empty());
-
-                    let mut chars = block.char_indices().peekable();
-                    while let Some((i, c)) = chars.next() {
-                        // Group \r\n as a single token, otherwise emit each char
-                        let word = if c == '\r' {
-                            if let Some(&(_, '\n')) = chars.peek() {
-                                let _ = chars.next();
-                                let end = i + 2;
-                                &block[i..end]
-                            } else {
-                                let end = i + c.len_utf8();
-                                &block[i..end]
-                            }
-                        } else {
-                            let end = i + c.len_utf8();
-                            &block[i..end]
-                        };
-                        tokens.push(Self::make_token_incremental(word, base, &mut unicode_offset));
-                    }
+                    Self::cut_unmatched(block, base, &mut unicode_offset, &mut tokens);
                 }
+                SplitState::Recognized(word) => {
+                    tokens.push(Self::make_token_incremental(word, base, &mut unicode_offset));
+                }
+            }
+        }
+        if let Some(index) = self.merge_units(sentence, &mut tokens)
+            && let Some(new_words) = new_words
+        {
+            for idx in new_words.iter_mut() {
+                *idx = index[*idx];
             }
+            new_words.dedup();
         }
         tokens
     }
 
+    /// Emit each char of a block without Han characters as a token, grouping \r\n
+    fn cut_unmatched<'a>(block: &'a str, base: usize, unicode_offset: &mut usize, tokens: &mut Vec<Token<'a>>) {
+        let mut chars = block.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            // Group \r\n as a single token, otherwise emit each char
+            let word = if c == '\r' {
+                if let Some(&(_, '\n')) = chars.peek() {
+                    let _ = chars.next();
+                    let end = i + 2;
+                    &block[i..end]
+                } else {
+                    let end = i + c.len_utf8();
+                    &block[i..end]
+                }
+            } else {
+                let end = i + c.len_utf8();
+                &block[i..end]
+            };
+            tokens.push(Self::make_token_incremental(word, base, unicode_offset));
+        }
+    }
+
     /// Dedicated top-level cut_all implementation that avoids allocating a byte-to-unicode table.
-    fn cut_all_toplevel<'a>(&self, sentence: &'a str) -> Vec<Token<'a>> {
+    fn cut_all_toplevel<'a>(&self, sentence: &'a str, recognized: &[(usize, usize, &PatternKind)]) -> Vec<Token<'a>> {
         let base = sentence.as_ptr() as usize;
         let mut unicode_offset = 0;
 
         let heuristic_capacity = sentence.len() / 2;
         let mut tokens = Vec::with_capacity(heuristic_capacity);
 
-        let splitter = SplitByCharacterClass::new(sentence, is_han_cut_all);
+        let splitter = self.split_blocks(sentence, recognized, |c| self.char_classes.is_han_cut_all(c));
 
         for state in splitter {
             match state {
@@ -855,7 +1640,7 @@ impl Jieba {
                     let block = state.as_str();
                     assert!(!block.is_empty());
 
-                    let skip_splitter = SplitByCharacterClass::new(block, is_skip_cut_all);
+                    let skip_splitter = SplitByCharacterClass::new(block, |c| self.char_classes.is_skip_cut_all(c));
                     for skip_state in skip_splitter {
                         let word = skip_state.as_str();
                         if word.is_empty() {
@@ -864,30 +1649,63 @@ impl Jieba {
                         if skip_state.is_matched() {
                             // Emit each char individually to match old RE_SKIP_CUT_ALL
                             // which matched single characters, not runs.
-                            let mut indices = word.char_indices().peekable();
-                            while let Some((i, _)) = indices.next() {
-                                let end = indices.peek().map_or(word.len(), |&(j, _)| j);
-                                tokens.push(Self::make_token_incremental(&word[i..end], base, &mut unicode_offset));
+                            let mut rest = word;
+                            while !rest.is_empty() {
+                                let len = self.unit_len(rest);
+                                tokens.push(Self::make_token_incremental(&rest[..len], base, &mut unicode_offset));
+                                rest = &rest[len..];
                             }
                         } else {
                             tokens.push(Self::make_token_incremental(word, base, &mut unicode_offset));
                         }
                     }
                 }
+                SplitState::Recognized(word) => {
+                    tokens.push(Self::make_token_incremental(word, base, &mut unicode_offset));
+                }
             }
         }
+        self.merge_units(sentence, &mut tokens);
         tokens
     }
 
-    /// Cut the input text
+    /// Cut the input text
+    ///
+    /// ## Params
+    ///
+    /// `sentence`: input text
+    ///
+    /// `hmm`: enable HMM or not
+    pub fn cut<'a>(&self, sentence: &'a str, hmm: bool) -> Vec<Token<'a>> {
+        self.cut_internal(sentence, false, hmm, None)
+    }
+
+    /// Cut the input text with HMM enabled and report the words discovered by the HMM
+    ///
+    /// The tokens are the same as the ones returned by `cut(sentence, true)`. In addition,
+    /// the indices of the tokens of at least two chars that were produced by the HMM for
+    /// out-of-vocabulary runs and are not in the dictionary are returned.
     ///
     /// ## Params
     ///
     /// `sentence`: input text
     ///
-    /// `hmm`: enable HMM or not
-    pub fn cut<'a>(&self, sentence: &'a str, hmm: bool) -> Vec<Token<'a>> {
-        self.cut_internal(sentence, false, hmm)
+    /// ## Example
+    ///
+    /// ```
+    /// use jieba_rs::Jieba;
+    ///
+    /// let mut jieba = Jieba::empty();
+    /// jieba.add_word("硕士", Some(1000), None);
+    /// let cut = jieba.cut_with_new_words("小明硕士毕业于中国科学院计算所");
+    /// let new_words: Vec<&str> = cut.new_words().map(|t| t.word).collect();
+    /// assert!(new_words.contains(&"小明"));
+    /// assert!(!new_words.contains(&"硕士"));
+    /// ```
+    pub fn cut_with_new_words<'a>(&self, sentence: &'a str) -> NewWordsCut<'a> {
+        let mut new_words = Vec::new();
+        let tokens = self.cut_internal(sentence, false, true, Some(&mut new_words));
+        NewWordsCut { tokens, new_words }
     }
 
     /// Cut the input text, return all possible words
@@ -896,7 +1714,7 @@ impl Jieba {
     ///
     /// `sentence`: input text
     pub fn cut_all<'a>(&self, sentence: &'a str) -> Vec<Token<'a>> {
-        self.cut_internal(sentence, true, false)
+        self.cut_internal(sentence, true, false, None)
     }
 
     /// Cut the input text in search mode
@@ -907,7 +1725,27 @@ impl Jieba {
     ///
     /// `hmm`: enable HMM or not
     pub fn cut_for_search<'a>(&self, sentence: &'a str, hmm: bool) -> Vec<Token<'a>> {
-        let words = self.cut(sentence, hmm);
+        match self.normalize(sentence) {
+            Some(normalized) => {
+                let recognized = self.recognized_spans(normalized.as_str());
+                normalized
+                    .map_tokens(&self.cut_for_search_raw(normalized.as_str(), &recognized, hmm))
+                    .into_iter()
+                    .map(|(token, _)| token)
+                    .collect()
+            }
+            None => self.cut_for_search_raw(sentence, &self.recognized_spans(sentence), hmm),
+        }
+    }
+
+    /// Cut the input text in search mode without normalization
+    fn cut_for_search_raw<'a>(
+        &self,
+        sentence: &'a str,
+        recognized: &[(usize, usize, &PatternKind)],
+        hmm: bool,
+    ) -> Vec<Token<'a>> {
+        let words = self.cut_raw(sentence, recognized, false, hmm, None);
         let mut new_words = Vec::with_capacity(words.len());
         let base = sentence.as_ptr() as usize;
         let mut char_indices = Vec::new();
@@ -924,7 +1762,7 @@ impl Jieba {
                     } else {
                         &word[local_byte_start..]
                     };
-                    if self.cedar.exact_match_search(gram2).is_some() {
+                    if self.word_id(gram2).is_some() {
                         let byte_start = gram2.as_ptr() as usize - base;
                         let byte_end = byte_start + gram2.len();
                         new_words.push(Token {
@@ -945,7 +1783,7 @@ impl Jieba {
                     } else {
                         &word[local_byte_start..]
                     };
-                    if self.cedar.exact_match_search(gram3).is_some() {
+                    if self.word_id(gram3).is_some() {
                         let byte_start = gram3.as_ptr() as usize - base;
                         let byte_end = byte_start + gram3.len();
                         new_words.push(Token {
@@ -987,40 +1825,380 @@ impl Jieba {
     ///
     /// `hmm`: enable HMM or not
     pub fn tag<'a>(&'a self, sentence: &'a str, hmm: bool) -> Vec<Tag<'a>> {
-        let tokens = self.cut(sentence, hmm);
-        tokens
+        self.tag_with(sentence, hmm, false).into_iter().map(|t| t.tag).collect()
+    }
+
+    /// Tag the tokens of the input text cut in search mode
+    ///
+    /// Tags are looked up for each token as in [`Jieba::tag`], the n-grams of long words
+    /// included.
+    pub(crate) fn tag_for_search<'a>(&'a self, sentence: &'a str, hmm: bool) -> Vec<Tag<'a>> {
+        match self.normalize(sentence) {
+            Some(normalized) => {
+                let spans = self.recognized_spans(normalized.as_str());
+                let tokens = self.cut_for_search_raw(normalized.as_str(), &spans, hmm);
+                normalized
+                    .map_tokens(&tokens)
+                    .into_iter()
+                    .map(|(token, range)| {
+                        let (word, kind) = normalized_word(&normalized, &tokens[range], &spans);
+                        self.tag_token(token, word, None, kind, false).tag
+                    })
+                    .collect()
+            }
+            None => {
+                let spans = self.recognized_spans(sentence);
+                self.cut_for_search_raw(sentence, &spans, hmm)
+                    .into_iter()
+                    .map(|token| {
+                        let kind = recognized_kind(&spans, &token);
+                        self.tag_token(token.clone(), token.word, None, kind, false).tag
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Cut, tag and split the input text into sentences in one pass, see [`Document`]
+    ///
+    /// ## Params
+    ///
+    /// `text`: input text
+    ///
+    /// `config`: segmentation mode, HMM, tagging and sentence splitter
+    pub fn document(&self, text: &str, config: DocumentConfig) -> Document {
+        Document::new(self, text, config)
+    }
+
+    /// Tag the input text, reporting where each tag comes from
+    ///
+    /// Words in the dictionary get their dictionary tag. Other words, and dictionary
+    /// words added without a tag, get a tag guessed by the POS tagging HMM, by the
+    /// `eng`/`m` heuristic for ASCII letters and digits, or the `x` fallback.
+    ///
+    /// ## Params
+    ///
+    /// `sentence`: input text
+    ///
+    /// `hmm`: enable HMM or not
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use jieba_rs::{Jieba, TagSource};
+    ///
+    /// let mut jieba = Jieba::empty();
+    /// jieba.add_word("学习", Some(100), Some("v"));
+    /// let tags = jieba.tag_detail("学习Rust", false);
+    /// assert_eq!(tags[0].source, TagSource::Dictionary);
+    /// assert_eq!(tags[1].tag.tag, "eng");
+    /// assert_eq!(tags[1].source, TagSource::Heuristic);
+    /// ```
+    pub fn tag_detail<'a>(&'a self, sentence: &'a str, hmm: bool) -> Vec<TagDetail<'a>> {
+        self.tag_with(sentence, hmm, true)
+    }
+
+    /// Tag the input text, computing the margins of the tags guessed by the POS tagging
+    /// HMM only if `margins` is true
+    fn tag_with<'a>(&'a self, sentence: &'a str, hmm: bool, margins: bool) -> Vec<TagDetail<'a>> {
+        match self.normalize(sentence) {
+            Some(normalized) => {
+                let spans = self.recognized_spans(normalized.as_str());
+                let tokens = self.cut_raw(normalized.as_str(), &spans, false, hmm, None);
+                normalized
+                    .map_tokens(&tokens)
+                    .into_iter()
+                    .map(|(token, range)| {
+                        let (word, kind) = normalized_word(&normalized, &tokens[range], &spans);
+                        self.tag_token(token, word, None, kind, margins)
+                    })
+                    .collect()
+            }
+            None => {
+                let spans = self.recognized_spans(sentence);
+                self.cut_raw(sentence, &spans, false, hmm, None)
+                    .into_iter()
+                    .map(|token| {
+                        let kind = recognized_kind(&spans, &token);
+                        self.tag_token(token.clone(), token.word, None, kind, margins)
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Tag the input text, segmenting and tagging out-of-vocabulary words together
+    ///
+    /// This is the mode of Python jieba's `posseg.cut`. [`Jieba::tag`] cuts the text with
+    /// the segmentation HMM first and then tags each token on its own. Here the runs of
+    /// single characters left by the dictionary are fed to the POS tagging model instead,
+    /// which decides both the word boundaries and the tags, giving better person and
+    /// place names. Dictionary words keep their dictionary tags.
+    ///
+    /// Without a POS tagging model, this is the same as `tag(sentence, true)`.
+    ///
+    /// ## Params
+    ///
+    /// `sentence`: input text
+    pub fn tag_joint<'a>(&'a self, sentence: &'a str) -> Vec<Tag<'a>> {
+        self.tag_joint_with(sentence, false)
             .into_iter()
-            .map(|token| {
-                let word = token.word;
-                if let Some((word_id, _, _)) = self.cedar.exact_match_search(word) {
-                    let t = &self.records[word_id as usize].tag;
-                    return Tag {
-                        word,
-                        tag: t,
-                        start: token.start,
-                        end: token.end,
-                        byte_start: token.byte_start,
-                        byte_end: token.byte_end,
-                    };
+            .map(|t| t.tag)
+            .collect()
+    }
+
+    /// Same as [`Jieba::tag_joint`], reporting where each tag comes from like [`Jieba::tag_detail`]
+    ///
+    /// ## Params
+    ///
+    /// `sentence`: input text
+    pub fn tag_joint_detail<'a>(&'a self, sentence: &'a str) -> Vec<TagDetail<'a>> {
+        self.tag_joint_with(sentence, true)
+    }
+
+    /// Same as [`Jieba::tag_with`], segmenting and tagging out-of-vocabulary words together
+    fn tag_joint_with<'a>(&'a self, sentence: &'a str, margins: bool) -> Vec<TagDetail<'a>> {
+        let Some(model) = self.posseg_model() else {
+            return self.tag_with(sentence, true, margins);
+        };
+        match self.normalize(sentence) {
+            Some(normalized) => {
+                let spans = self.recognized_spans(normalized.as_str());
+                let (tokens, mut guesses): (Vec<_>, Vec<_>) = self
+                    .cut_posseg(normalized.as_str(), &spans, model, margins)
+                    .into_iter()
+                    .unzip();
+                normalized
+                    .map_tokens(&tokens)
+                    .into_iter()
+                    .map(|(token, range)| {
+                        // A word merged from several tagged tokens is tagged again as a whole
+                        let guess = if range.len() == 1 {
+                            guesses[range.start].take()
+                        } else {
+                            None
+                        };
+                        let (word, kind) = normalized_word(&normalized, &tokens[range], &spans);
+                        self.tag_token(token, word, guess, kind, margins)
+                    })
+                    .collect()
+            }
+            None => {
+                let spans = self.recognized_spans(sentence);
+                self.cut_posseg(sentence, &spans, model, margins)
+                    .into_iter()
+                    .map(|(token, guess)| {
+                        let kind = recognized_kind(&spans, &token);
+                        self.tag_token(token.clone(), token.word, guess, kind, margins)
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Cut the input text with the POS tagging model for out-of-vocabulary runs, without
+    /// normalization
+    fn cut_posseg<'a, 'b>(
+        &self,
+        sentence: &'a str,
+        recognized: &[(usize, usize, &PatternKind)],
+        model: &'b PossegModel,
+        margins: bool,
+    ) -> Vec<(Token<'a>, Option<PosGuess<'b>>)> {
+        let base = sentence.as_ptr() as usize;
+        let mut unicode_offset = 0;
+
+        let heuristic_capacity = sentence.len() / 2;
+        let mut words = Vec::with_capacity(heuristic_capacity);
+        let mut tokens = Vec::with_capacity(heuristic_capacity);
+        let mut guesses = Vec::with_capacity(heuristic_capacity);
+        let mut route = Vec::with_capacity(heuristic_capacity);
+        let mut dag = StaticSparseDAG::with_size_hint(heuristic_capacity);
+
+        for state in self.split_blocks(sentence, recognized, |c| self.char_classes.is_han_default(c)) {
+            let block = state.as_str();
+            assert!(!block.is_empty());
+            match state {
+                SplitState::Matched(_) => {
+                    words.clear();
+                    self.cut_dag_posseg(block, model, margins, &mut words, &mut route, &mut dag);
+                    for &(word, guess) in &words {
+                        tokens.push(Self::make_token_incremental(word, base, &mut unicode_offset));
+                        guesses.push(guess);
+                    }
                 }
-                let tag = self.guess_tag(word);
-                Tag {
-                    word,
-                    tag,
-                    start: token.start,
-                    end: token.end,
-                    byte_start: token.byte_start,
-                    byte_end: token.byte_end,
+                SplitState::Unmatched(_) => {
+                    let first = tokens.len();
+                    Self::cut_unmatched(block, base, &mut unicode_offset, &mut tokens);
+                    guesses.resize(guesses.len() + tokens.len() - first, None);
                 }
-            })
-            .collect()
+                SplitState::Recognized(word) => {
+                    tokens.push(Self::make_token_incremental(word, base, &mut unicode_offset));
+                    guesses.push(None);
+                }
+            }
+        }
+        if let Some(index) = self.merge_units(sentence, &mut tokens) {
+            // Tokens merged from several ones are tagged like out-of-vocabulary words
+            let mut merged = vec![None; tokens.len()];
+            for (i, &j) in index.iter().enumerate() {
+                let single = (i == 0 || index[i - 1] != j) && index.get(i + 1) != Some(&j);
+                if single {
+                    merged[j] = guesses[i];
+                }
+            }
+            guesses = merged;
+        }
+        tokens.into_iter().zip(guesses).collect()
+    }
+
+    /// Extract person, place, organization and time entities from the input text
+    ///
+    /// The text is tagged with [`Jieba::tag_joint`], then adjacent tokens are merged
+    /// into entities as described in [`Entity::from_tags`].
+    ///
+    /// ## Params
+    ///
+    /// `sentence`: input text
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use jieba_rs::{EntityKind, Jieba};
+    ///
+    /// let mut jieba = Jieba::empty();
+    /// jieba.add_word("清华", Some(100), Some("nz"));
+    /// jieba.add_word("大学", Some(100), Some("n"));
+    /// let entities = jieba.entities("清华大学");
+    /// assert_eq!(entities[0].text, "清华大学");
+    /// assert_eq!(entities[0].kind, EntityKind::Organization);
+    /// ```
+    pub fn entities<'a>(&'a self, sentence: &'a str) -> Vec<Entity<'a>> {
+        let tags = self.tag_joint(sentence);
+        Entity::from_tags(sentence, &tags)
+    }
+
+    /// Extract numbers with their units from the input text
+    ///
+    /// The text is tagged without HMM, then numbers are merged with their units as
+    /// described in [`Quantity::from_tags`].
+    ///
+    /// ## Params
+    ///
+    /// `sentence`: input text
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use jieba_rs::{Jieba, QuantityKind};
+    ///
+    /// let jieba = Jieba::empty();
+    /// let quantities = jieba.quantities("增资4.3亿元");
+    /// assert_eq!(quantities[0].text, "4.3亿元");
+    /// assert_eq!(quantities[0].value, 430_000_000.0);
+    /// assert_eq!(quantities[0].unit, "元");
+    /// ```
+    pub fn quantities<'a>(&'a self, sentence: &'a str) -> Vec<Quantity<'a>> {
+        let tags = self.tag(sentence, false);
+        Quantity::from_tags(sentence, &tags)
+    }
+
+    /// Extract time expressions from the input text and resolve them against `reference`
+    ///
+    /// The text is tagged without HMM, then adjacent tokens are merged into time
+    /// expressions as described in [`TimeExpression::from_tags`].
+    ///
+    /// ## Params
+    ///
+    /// `sentence`: input text
+    ///
+    /// `reference`: datetime the text is relative to, such as the time it was written
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use jieba_rs::{DateTime, Jieba, TimeUnit, TimeValue};
+    ///
+    /// let jieba = Jieba::empty();
+    /// let reference = DateTime::new(2013, 5, 1, 10, 0, 0).unwrap();
+    /// let times = jieba.time_expressions("3天后", reference);
+    /// assert_eq!(times[0].text, "3天后");
+    /// assert_eq!(
+    ///     times[0].value,
+    ///     TimeValue::Point {
+    ///         datetime: DateTime::new(2013, 5, 4, 10, 0, 0).unwrap(),
+    ///         granularity: TimeUnit::Day,
+    ///     }
+    /// );
+    /// ```
+    pub fn time_expressions<'a>(&'a self, sentence: &'a str, reference: DateTime) -> Vec<TimeExpression<'a>> {
+        let tags = self.tag(sentence, false);
+        TimeExpression::from_tags(sentence, &tags, reference)
+    }
+
+    /// Tag a token, with `guess` as the tag and margin given by the POS tagging model if any
+    ///
+    /// `word` is the normalized form of the token, looked up in the dictionary. `kind` is the
+    /// kind of the token if it was recognized by the recognizer.
+    fn tag_token<'a>(
+        &'a self,
+        token: Token<'a>,
+        word: &str,
+        guess: Option<PosGuess<'a>>,
+        kind: Option<&'a PatternKind>,
+        margins: bool,
+    ) -> TagDetail<'a> {
+        let (tag, source, margin) = match (kind, guess) {
+            (Some(kind), _) => (kind.as_str(), TagSource::Pattern, None),
+            (
+                None,
+                Some(PosGuess {
+                    tag, fallback: true, ..
+                }),
+            ) => (tag, TagSource::Fallback, None),
+            (None, Some(PosGuess { tag, margin, .. })) => (tag, TagSource::Hmm, margin),
+            (None, None) => match self.word_id(word) {
+                Some(word_id) if !self.records[word_id as usize].tag.is_empty() => {
+                    (&*self.records[word_id as usize].tag, TagSource::Dictionary, None)
+                }
+                _ => self.guess_tag(word, margins),
+            },
+        };
+        TagDetail {
+            tag: Tag {
+                word: token.word,
+                tag,
+                start: token.start,
+                end: token.end,
+                byte_start: token.byte_start,
+                byte_end: token.byte_end,
+            },
+            source,
+            margin,
+        }
+    }
+
+    /// The POS tagging model in use, if any.
+    fn posseg_model(&self) -> Option<&PossegModel> {
+        if let Some(ref model) = self.posseg_model {
+            return Some(model);
+        }
+        #[cfg(feature = "default-dict")]
+        {
+            Some(posseg::posseg_data())
+        }
+        #[cfg(not(feature = "default-dict"))]
+        {
+            None
+        }
     }
 
-    /// Guess the POS tag for an OOV word.
+    /// Guess the POS tag for an OOV word, with its source and, if `margins` is true, its margin.
     ///
     /// For CJK words, uses the posseg HMM model (when available) to predict the tag.
     /// For ASCII words, uses simple heuristics (digits → "m", alpha → "eng", else → "x").
-    fn guess_tag(&self, word: &str) -> &'static str {
+    /// Words made of Chinese numerals are tagged "m".
+    fn guess_tag(&self, word: &str, margins: bool) -> (&str, TagSource, Option<f64>) {
         let mut eng = 0;
         let mut m = 0;
         for chr in word.chars() {
@@ -1032,38 +2210,48 @@ impl Jieba {
             }
         }
         if eng > 0 {
-            return if eng == m { "m" } else { "eng" };
+            return (if eng == m { "m" } else { "eng" }, TagSource::Heuristic, None);
+        }
+        if quantity::number_len(word) == word.len() {
+            return ("m", TagSource::Heuristic, None);
         }
 
-        #[cfg(feature = "default-dict")]
+        // Only use posseg HMM for words containing CJK characters
+        if let Some(model) = self.posseg_model()
+            && word.chars().any(|c| self.char_classes.is_cjk(c))
         {
-            // Only use posseg HMM for words containing CJK characters
-            if word.chars().any(|c| is_cjk(c)) {
-                let results = posseg::cut_with_pos(word);
-                if results.len() == 1 {
-                    return results[0].1;
-                }
-                if let Some((_w, tag)) = results.iter().max_by_key(|(w, _)| w.len()) {
-                    return tag;
-                }
+            let results = posseg::cut_with_pos_margins(model, word, margins);
+            if let Some(&(_w, PosGuess { tag, fallback, margin })) = results.iter().max_by_key(|(w, _)| w.len()) {
+                return if fallback {
+                    (tag, TagSource::Fallback, None)
+                } else {
+                    (tag, TagSource::Hmm, margin)
+                };
             }
         }
 
-        "x"
+        ("x", TagSource::Fallback, None)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Jieba, SplitByCharacterClass, SplitState, TokenizeMode, is_han_default};
+    use super::{
+        Jieba, Normalizer, Recognizer, SplitByCharacterClass, SplitState, fold_case, is_han_default, parse_dict_line,
+    };
+    #[cfg(feature = "default-dict")]
+    use super::{TagSource, TokenizeMode};
     use expect_test::expect;
+    use std::borrow::Cow;
     use std::io::BufReader;
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_init_with_default_dict() {
         let _ = Jieba::new();
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_has_word() {
         let jieba = Jieba::new();
@@ -1084,7 +2272,7 @@ mod tests {
                     let block = state.as_str();
                     assert!(!block.is_empty());
                 }
-                SplitState::Unmatched(_) => {
+                SplitState::Unmatched(_) | SplitState::Recognized(_) => {
                     let block = state.as_str();
                     assert!(!block.is_empty());
                 }
@@ -1100,6 +2288,7 @@ mod tests {
         expect![[r#"["讥䶯䶰䶱䶲䶳䶴䶵𦡦"]"#]].assert_eq(&format!("{:?}", result));
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_cut_all_skip_single_char() {
         let jieba = Jieba::new();
@@ -1107,6 +2296,7 @@ mod tests {
         assert_eq!(words, vec!["a", "！", "！", "b"]);
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_cut_default_crlf_and_whitespace() {
         let jieba = Jieba::new();
@@ -1114,6 +2304,7 @@ mod tests {
         assert_eq!(words, vec!["x", "\r\n", "\t", "y"]);
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_cut_all() {
         let jieba = Jieba::new();
@@ -1131,6 +2322,7 @@ mod tests {
             .assert_eq(&format!("{:?}", words));
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_cut_no_hmm() {
         let jieba = Jieba::new();
@@ -1139,6 +2331,7 @@ mod tests {
         expect![[r#"["abc", "网球", "拍卖会", "def"]"#]].assert_eq(&format!("{:?}", words));
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_cut_no_hmm1() {
         let jieba = Jieba::new();
@@ -1148,6 +2341,7 @@ mod tests {
             .assert_eq(&format!("{:?}", words));
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_cut_with_hmm() {
         let jieba = Jieba::new();
@@ -1170,6 +2364,7 @@ mod tests {
         expect![[r#"["他", "来到", "了", "网易", "杭研", "大厦"]"#]].assert_eq(&format!("{:?}", words));
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_cut_weicheng() {
         static WEICHENG_TXT: &str = include_str!("../../examples/weicheng/src/weicheng.txt");
@@ -1179,6 +2374,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_cut_for_search() {
         let jieba = Jieba::new();
@@ -1195,6 +2391,7 @@ mod tests {
             .assert_eq(&format!("{:?}", words));
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_tag() {
         let jieba = Jieba::new();
@@ -1542,6 +2739,7 @@ mod tests {
         .assert_eq(&format!("{:#?}", tags));
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_tokenize() {
         let jieba = Jieba::new();
@@ -1736,6 +2934,7 @@ mod tests {
         .assert_eq(&format!("{:#?}", tokens));
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_userdict() {
         let mut jieba = Jieba::new();
@@ -1830,6 +3029,7 @@ mod tests {
         .assert_eq(&format!("{:#?}", tokens));
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_userdict_hmm() {
         let mut jieba = Jieba::new();
@@ -1964,6 +3164,7 @@ mod tests {
         assert!(ret.is_err());
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_suggest_freq() {
         // NOTE: Following behaviors are aligned with original Jieba
@@ -1985,6 +3186,7 @@ mod tests {
         assert_eq!(jieba.suggest_freq("中出"), 500)
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_custom_lower_freq() {
         let mut jieba = Jieba::new();
@@ -2018,6 +3220,7 @@ mod tests {
         expect![[r#"["市", "民", "田-女士", "急", "匆", "匆"]"#]].assert_eq(&format!("{:?}", words));
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_cut_with_custom_hmm_model() {
         use crate::hmm::HmmModel;
@@ -2044,4 +3247,308 @@ mod tests {
             assert_eq!(custom_words, builtin_words, "mismatch for: {sentence}");
         }
     }
+
+    #[cfg(feature = "default-dict")]
+    #[test]
+    fn test_tag_joint() {
+        let mut jieba = Jieba::empty();
+        jieba.add_word("硕士", Some(1000), Some("n"));
+        jieba.add_word("毕业", Some(1000), Some("v"));
+        jieba.add_word("计算所", Some(1000), Some("n"));
+
+        let sentence = "小明硕士毕业于中国科学院计算所，2013年";
+        let tags = jieba.tag_joint(sentence);
+        let words: Vec<String> = tags.iter().map(|t| format!("{}/{}", t.word, t.tag)).collect();
+        expect![[r#"["小明/nr", "硕士/n", "毕业/v", "于/p", "中国科学院/nt", "计算所/n", "，/x", "2013/m", "年/m"]"#]]
+            .assert_eq(&format!("{:?}", words));
+
+        let tokens = jieba.cut(sentence, true);
+        assert_eq!(tags.last().unwrap().byte_end, sentence.len());
+        assert_eq!(tags.last().unwrap().end, tokens.last().unwrap().end);
+    }
+
+    #[cfg(feature = "default-dict")]
+    #[test]
+    fn test_tag_detail() {
+        let mut jieba = Jieba::empty();
+        jieba.add_word("学习", Some(100), Some("v"));
+        jieba.add_word("北京", Some(100), None);
+
+        let tags = jieba.tag_detail("在北京学习Rust，", false);
+        let sources: Vec<(&str, &str, TagSource)> = tags.iter().map(|t| (t.tag.word, t.tag.tag, t.source)).collect();
+        assert_eq!(
+            sources,
+            vec![
+                ("在", "p", TagSource::Hmm),
+                ("北京", "ns", TagSource::Hmm),
+                ("学习", "v", TagSource::Dictionary),
+                ("Rust", "eng", TagSource::Heuristic),
+                ("，", "x", TagSource::Fallback),
+            ]
+        );
+        for tag in &tags {
+            assert_eq!(tag.margin.is_some(), tag.source == TagSource::Hmm);
+            assert!(tag.margin.is_none_or(|m| m >= 0.0));
+        }
+
+        let joint = jieba.tag_joint_detail("在北京学习Rust，");
+        assert_eq!(joint[2].source, TagSource::Dictionary);
+        assert!(joint[0].margin.is_some());
+    }
+
+    #[test]
+    fn test_parse_dict_line() {
+        let fields = |line| parse_dict_line(line).map(|(word, freq, tag)| (word.into_owned(), freq, tag));
+        assert_eq!(
+            fields("北京 100 ns\n"),
+            Some(("北京".to_string(), Some("100"), Some("ns")))
+        );
+        assert_eq!(fields("北京\n"), Some(("北京".to_string(), None, None)));
+        assert_eq!(
+            fields("New York\t100\tns\n"),
+            Some(("New York".to_string(), Some("100"), Some("ns")))
+        );
+        assert_eq!(
+            fields("\"New York\" 100 ns"),
+            Some(("New York".to_string(), Some("100"), Some("ns")))
+        );
+        assert_eq!(
+            fields(r#""say \"hi\"" 3"#),
+            Some((r#"say "hi""#.to_string(), Some("3"), None))
+        );
+        assert_eq!(
+            fields("北京\t100 ns\n"),
+            Some(("北京".to_string(), Some("100"), Some("ns")))
+        );
+        assert_eq!(
+            fields("北京 100\tns\n"),
+            Some(("北京".to_string(), Some("100"), Some("ns")))
+        );
+        assert_eq!(fields("北京\t100\n"), Some(("北京".to_string(), Some("100"), None)));
+        assert_eq!(fields("\" 3 x"), Some(("\"".to_string(), Some("3"), Some("x"))));
+        assert_eq!(fields("  \n"), None);
+    }
+
+    #[test]
+    fn test_separator_words() {
+        let dict = "我 100 r\n在 100 p\n工作 100 vn\n\"New York\" 100 ns\n迈克尔·乔丹\t100\tnr\n";
+        let jieba = Jieba::with_dict(&mut dict.as_bytes()).unwrap();
+        assert!(jieba.has_word("New York"));
+
+        for hmm in [false, true] {
+            let words: Vec<&str> = jieba.cut("我在New York工作", hmm).into_iter().map(|t| t.word).collect();
+            assert_eq!(words, vec!["我", "在", "New York", "工作"]);
+            let words: Vec<&str> = jieba
+                .cut("迈克尔·乔丹在New  York", hmm)
+                .into_iter()
+                .map(|t| t.word)
+                .collect();
+            assert_eq!(words[0], "迈克尔·乔丹");
+            assert!(words.ends_with(&["New", " ", " ", "York"]));
+        }
+        let words: Vec<&str> = jieba.cut_all("在New York工作").into_iter().map(|t| t.word).collect();
+        assert!(words.contains(&"New York"));
+        let tags = jieba.tag("迈克尔·乔丹", false);
+        assert_eq!((tags[0].word, tags[0].tag), ("迈克尔·乔丹", "nr"));
+    }
+
+    #[test]
+    fn test_separator_words_other_modes() {
+        let mut jieba = Jieba::empty();
+        jieba.add_word("买", Some(100), Some("v"));
+        jieba.add_word("T恤", Some(100), Some("n"));
+        let cut_all = |jieba: &Jieba| -> Vec<String> {
+            jieba.cut_all("买T恤").into_iter().map(|t| t.word.to_string()).collect()
+        };
+        let before = cut_all(&jieba);
+        jieba.add_word("New York", Some(100), Some("ns"));
+        assert_eq!(cut_all(&jieba), before);
+
+        let words: Vec<&str> = jieba.cut("去NEW YORK", false).into_iter().map(|t| t.word).collect();
+        assert_eq!(words, vec!["去", "NEW", " ", "YORK"]);
+        jieba.set_case_insensitive(true);
+        let words: Vec<&str> = jieba.cut("去NEW YORK", false).into_iter().map(|t| t.word).collect();
+        assert_eq!(words, vec!["去", "NEW YORK"]);
+
+        jieba.clear();
+        assert!(!jieba.separator_words);
+    }
+
+    #[test]
+    fn test_recognizer() {
+        let mut jieba = Jieba::empty();
+        jieba.add_word("话题", Some(100), Some("n"));
+        jieba.add_word("看", Some(100), Some("v"));
+        let text = "@张三 看 https://example.com/a?b=1 #话题# v1.2.3";
+        let words: Vec<&str> = jieba.cut(text, false).into_iter().map(|t| t.word).collect();
+        assert!(words.contains(&"/"));
+
+        let mut recognizer = Recognizer::builtin();
+        recognizer.add_pattern("order", r"订单\d+").unwrap();
+        jieba.set_recognizer(recognizer);
+        for hmm in [false, true] {
+            let words: Vec<&str> = jieba.cut(text, hmm).into_iter().map(|t| t.word).collect();
+            assert_eq!(
+                words,
+                vec![
+                    "@张三",
+                    " ",
+                    "看",
+                    " ",
+                    "https://example.com/a?b=1",
+                    " ",
+                    "#话题#",
+                    " ",
+                    "v1.2.3"
+                ]
+            );
+        }
+        let words: Vec<&str> = jieba.cut_all("看订单123").into_iter().map(|t| t.word).collect();
+        assert_eq!(words, vec!["看", "订单123"]);
+
+        let tags = jieba.tag(text, true);
+        let tags: Vec<(&str, &str)> = tags.iter().map(|t| (t.word, t.tag)).collect();
+        assert_eq!(tags[0], ("@张三", "mention"));
+        assert_eq!(tags[2], ("看", "v"));
+        assert_eq!(tags[4], ("https://example.com/a?b=1", "url"));
+        assert_eq!(tags[6], ("#话题#", "hashtag"));
+        assert_eq!(tags[8], ("v1.2.3", "version"));
+        let tags = jieba.tag_joint("看订单42");
+        assert_eq!((tags[1].word, tags[1].tag, tags[1].start), ("订单42", "order", 1));
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        let mut jieba = Jieba::empty();
+        jieba.add_word("iPhone", Some(100), Some("nz"));
+        jieba.add_word("Pro", Some(100), Some("eng"));
+        jieba.add_word("iPhonePro", Some(50), Some("nz"));
+        jieba.add_word("手机", Some(100), Some("n"));
+        jieba.add_word("Café", Some(100), Some("n"));
+        jieba.set_case_insensitive(true);
+        assert!(jieba.has_word("IPHONE"));
+        assert!(jieba.has_word("CAFÉ"));
+
+        let text = "买IPHONEPRO手机";
+        for hmm in [false, true] {
+            let words: Vec<&str> = jieba.cut(text, hmm).into_iter().map(|t| t.word).collect();
+            assert_eq!(words, vec!["买", "IPHONEPRO", "手机"]);
+        }
+        let words: Vec<&str> = jieba.cut_for_search(text, false).into_iter().map(|t| t.word).collect();
+        assert_eq!(words, vec!["买", "PRO", "IPHONEPRO", "手机"]);
+        let tags = jieba.tag("IPhone", false);
+        assert_eq!((tags[0].word, tags[0].tag), ("IPhone", "nz"));
+
+        jieba.add_word("iphone", Some(10), Some("n"));
+        let tags = jieba.tag("IPHONE", false);
+        assert_eq!(tags[0].tag, "n");
+        let tags = jieba.tag("iPhone", false);
+        assert_eq!(tags[0].tag, "nz");
+        let tags = jieba.tag("手机iPhone", false);
+        assert_eq!((tags[1].word, tags[1].tag), ("iPhone", "nz"));
+
+        jieba.set_case_insensitive(false);
+        assert!(!jieba.has_word("IPHONE"));
+    }
+
+    #[test]
+    fn test_fold_case() {
+        assert_eq!(fold_case("中文abc"), Cow::Borrowed("中文abc"));
+        assert_eq!(fold_case("ABC Ésσ"), "abc ésσ");
+        // The Kelvin sign lower-cases to a shorter `k` and is kept
+        assert_eq!(fold_case("\u{212A}Ω"), "\u{212A}ω");
+    }
+
+    #[test]
+    fn test_merge_whitespace() {
+        let mut jieba = Jieba::empty();
+        jieba.add_word("工作", Some(100), Some("vn"));
+        jieba.set_merge_whitespace(true);
+        for hmm in [false, true] {
+            let tokens = jieba.cut("工作  \t工作 ", hmm);
+            let words: Vec<&str> = tokens.iter().map(|t| t.word).collect();
+            assert_eq!(words, vec!["工作", "  \t", "工作", " "]);
+            assert_eq!((tokens[1].start, tokens[1].end), (2, 5));
+        }
+        let words: Vec<&str> = jieba.cut_all("工作  工作").into_iter().map(|t| t.word).collect();
+        assert_eq!(words, vec!["工作", "  ", "工作"]);
+        let tags = jieba.tag("工作  ", true);
+        assert_eq!((tags[1].word, tags[1].tag), ("  ", "x"));
+    }
+
+    #[cfg(feature = "grapheme")]
+    #[test]
+    fn test_grapheme_clusters() {
+        let mut jieba = Jieba::empty();
+        jieba.add_word("好", Some(100), Some("a"));
+        jieba.set_grapheme_clusters(true);
+        let text = "好👨\u{200d}👩\u{200d}👧🇨🇳e\u{301}✌\u{fe0f}";
+        for hmm in [false, true] {
+            let words: Vec<&str> = jieba.cut(text, hmm).into_iter().map(|t| t.word).collect();
+            assert_eq!(
+                words,
+                vec!["好", "👨\u{200d}👩\u{200d}👧", "🇨🇳", "e\u{301}", "✌\u{fe0f}"]
+            );
+        }
+        let words: Vec<&str> = jieba.cut_all(text).into_iter().map(|t| t.word).collect();
+        assert_eq!(
+            words,
+            vec!["好", "👨\u{200d}👩\u{200d}👧", "🇨🇳", "e\u{301}", "✌\u{fe0f}"]
+        );
+    }
+
+    #[test]
+    fn test_normalizer() {
+        let mut jieba = Jieba::empty();
+        jieba.add_word("ABC", Some(100), Some("nz"));
+        jieba.add_word("语言学", Some(100), Some("n"));
+        jieba.add_word("语言", Some(100), Some("n"));
+        let mut normalizer = Normalizer::new().fold_width(true);
+        normalizer.add_conversion("語", "语");
+        normalizer.add_conversion("學", "学");
+        jieba.set_normalizer(normalizer);
+
+        let sentence = "ＡＢＣ語言學";
+        let tokens: Vec<(&str, usize, usize, usize, usize)> = jieba
+            .cut_for_search(sentence, false)
+            .into_iter()
+            .map(|t| (t.word, t.start, t.end, t.byte_start, t.byte_end))
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![("ＡＢＣ", 0, 3, 0, 9), ("語言", 3, 5, 9, 15), ("語言學", 3, 6, 9, 18)]
+        );
+
+        let tags: Vec<(&str, &str)> = jieba
+            .tag(sentence, false)
+            .into_iter()
+            .map(|t| (t.word, t.tag))
+            .collect();
+        assert_eq!(tags, vec![("ＡＢＣ", "nz"), ("語言學", "n")]);
+        let joint: Vec<&str> = jieba.tag_joint(sentence).into_iter().map(|t| t.word).collect();
+        assert_eq!(joint, vec!["ＡＢＣ", "語言學"]);
+    }
+
+    #[cfg(feature = "nfkc")]
+    #[test]
+    fn test_normalizer_expansion() {
+        let mut jieba = Jieba::empty();
+        jieba.add_word("元年", Some(100), Some("t"));
+        jieba.set_normalizer(Normalizer::new().nfkc(true));
+
+        let sentence = "㍻元年";
+        let tokens: Vec<(&str, usize, usize)> = jieba
+            .cut(sentence, false)
+            .into_iter()
+            .map(|t| (t.word, t.start, t.end))
+            .collect();
+        assert_eq!(tokens, vec![("㍻", 0, 1), ("元年", 1, 3)]);
+        let new_words = jieba.cut_with_new_words(sentence);
+        assert_eq!(new_words.tokens.len(), 2);
+
+        let tags: Vec<&str> = jieba.tag(sentence, false).into_iter().map(|t| t.word).collect();
+        assert_eq!(tags, vec!["㍻", "元年"]);
+        let joint: Vec<&str> = jieba.tag_joint(sentence).into_iter().map(|t| t.word).collect();
+        assert_eq!(joint, vec!["㍻", "元年"]);
+    }
 }
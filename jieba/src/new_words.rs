@@ -0,0 +1,176 @@
+use crate::FxHashMap;
+use crate::{Jieba, Token};
+
+/// Result of [`Jieba::cut_with_new_words`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewWordsCut<'a> {
+    /// All tokens of the input, identical to `cut(sentence, true)`
+    pub tokens: Vec<Token<'a>>,
+    /// Indices into `tokens` of the words discovered by the HMM that are not in the dictionary
+    pub new_words: Vec<usize>,
+}
+
+impl<'a> NewWordsCut<'a> {
+    /// Iterate over the tokens discovered by the HMM that are not in the dictionary
+    pub fn new_words(&self) -> impl Iterator<Item = &Token<'a>> + '_ {
+        self.new_words.iter().map(|&idx| &self.tokens[idx])
+    }
+
+    /// Whether the token at `idx` was discovered by the HMM and is not in the dictionary
+    pub fn is_new_word(&self, idx: usize) -> bool {
+        self.new_words.binary_search(&idx).is_ok()
+    }
+}
+
+/// The neighbouring tokens of a new word occurrence
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NewWordContext {
+    /// Token before the new word, empty at the start of the input
+    pub left: String,
+    /// Token after the new word, empty at the end of the input
+    pub right: String,
+}
+
+/// Corpus-level statistics of a new word
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct NewWordStats {
+    /// Number of occurrences
+    pub count: usize,
+    /// Sample contexts, at most `max_contexts` of [`NewWordCounter`] distinct ones
+    pub contexts: Vec<NewWordContext>,
+}
+
+/// Aggregates the words discovered by the HMM over a corpus
+///
+/// # Examples
+///
+/// ```
+/// use jieba_rs::{Jieba, NewWordCounter};
+///
+/// let jieba = Jieba::empty();
+/// let mut counter = NewWordCounter::default();
+/// counter.add(&jieba, "小明硕士毕业于中国科学院计算所");
+/// counter.add(&jieba, "小明后在日本京都大学深造");
+/// assert_eq!(counter.get("小明").unwrap().count, 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NewWordCounter {
+    words: FxHashMap<String, NewWordStats>,
+    max_contexts: usize,
+}
+
+impl Default for NewWordCounter {
+    /// Creates a NewWordCounter keeping up to 5 contexts per word
+    fn default() -> Self {
+        NewWordCounter::new(5)
+    }
+}
+
+impl NewWordCounter {
+    /// Creates a NewWordCounter keeping up to `max_contexts` distinct contexts per word
+    pub fn new(max_contexts: usize) -> Self {
+        NewWordCounter {
+            words: FxHashMap::default(),
+            max_contexts,
+        }
+    }
+
+    /// Segment `sentence` with HMM enabled and count the new words found in it
+    pub fn add(&mut self, jieba: &Jieba, sentence: &str) {
+        let cut = jieba.cut_with_new_words(sentence);
+        self.add_cut(&cut);
+    }
+
+    /// Count the new words of an existing [`NewWordsCut`]
+    pub fn add_cut(&mut self, cut: &NewWordsCut<'_>) {
+        for &idx in &cut.new_words {
+            let word = cut.tokens[idx].word;
+            let stats = match self.words.get_mut(word) {
+                Some(stats) => stats,
+                None => self.words.entry(word.to_string()).or_default(),
+            };
+            stats.count += 1;
+
+            if stats.contexts.len() < self.max_contexts {
+                let context = NewWordContext {
+                    left: idx
+                        .checked_sub(1)
+                        .map_or_else(String::new, |i| cut.tokens[i].word.to_string()),
+                    right: cut.tokens.get(idx + 1).map_or_else(String::new, |t| t.word.to_string()),
+                };
+                if !stats.contexts.contains(&context) {
+                    stats.contexts.push(context);
+                }
+            }
+        }
+    }
+
+    /// Gets the statistics of `word`, if it has been seen as a new word
+    pub fn get(&self, word: &str) -> Option<&NewWordStats> {
+        self.words.get(word)
+    }
+
+    /// Number of distinct new words
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns `true` if no new word has been seen
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns the new words ordered by descending count, ties broken by the word itself
+    pub fn into_sorted(self) -> Vec<(String, NewWordStats)> {
+        let mut words: Vec<(String, NewWordStats)> = self.words.into_iter().collect();
+        words.sort_by(|a, b| b.1.count.cmp(&a.1.count).then_with(|| a.0.cmp(&b.0)));
+        words
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cut_with_new_words() {
+        let mut jieba = Jieba::empty();
+        jieba.add_word("硕士", Some(1000), None);
+        jieba.add_word("计算所", Some(1000), None);
+
+        let cut = jieba.cut_with_new_words("小明硕士毕业于中国科学院计算所abc");
+        assert_eq!(cut.tokens, jieba.cut("小明硕士毕业于中国科学院计算所abc", true));
+
+        let new_words: Vec<&str> = cut.new_words().map(|t| t.word).collect();
+        assert!(new_words.contains(&"小明"));
+        assert!(!new_words.contains(&"硕士"));
+        assert!(!new_words.contains(&"计算所"));
+        assert!(!new_words.contains(&"abc"));
+        assert!(!new_words.contains(&"于"));
+        assert!(new_words.iter().all(|w| w.chars().count() >= 2));
+        for &idx in &cut.new_words {
+            assert!(cut.is_new_word(idx));
+        }
+    }
+
+    #[test]
+    fn test_new_word_counter() {
+        let jieba = Jieba::empty();
+        let mut counter = NewWordCounter::new(1);
+        counter.add(&jieba, "小明硕士毕业于中国科学院计算所");
+        counter.add(&jieba, "，小明后在日本京都大学深造");
+
+        let stats = counter.get("小明").unwrap();
+        assert_eq!(stats.count, 2);
+        assert_eq!(
+            stats.contexts,
+            vec![NewWordContext {
+                left: String::new(),
+                right: "硕士".to_string(),
+            }]
+        );
+
+        let sorted = counter.into_sorted();
+        assert_eq!(sorted[0].0, "小明");
+    }
+}
@@ -0,0 +1,463 @@
+use std::io::BufRead;
+use std::ops::Range;
+
+#[cfg(feature = "nfkc")]
+use unicode_normalization::UnicodeNormalization;
+#[cfg(feature = "nfkc")]
+use unicode_normalization::char::is_combining_mark;
+
+use crate::errors::Error;
+use crate::{FxHashMap, Token};
+
+/// Text normalization applied before segmentation
+///
+/// Normalization makes variant forms of the input match the dictionary: full-width
+/// letters and digits such as `ＡＢＣ１２３` are folded to `ABC123`, and traditional
+/// characters can be converted to simplified ones with a conversion table. Set it on a
+/// [`Jieba`](crate::Jieba) instance with [`Jieba::set_normalizer`](crate::Jieba::set_normalizer),
+/// the tokens then still point into the original input.
+///
+/// # Examples
+///
+/// ```
+/// use jieba_rs::Normalizer;
+///
+/// let mut normalizer = Normalizer::new().fold_width(true);
+/// normalizer.add_conversion("資料", "资料");
+///
+/// let normalized = normalizer.normalize("ＡＢＣ資料");
+/// assert_eq!(normalized.as_str(), "ABC资料");
+/// assert_eq!(normalized.original_range(3, 9), 9..15);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Normalizer {
+    fold_width: bool,
+    #[cfg(feature = "nfkc")]
+    nfkc: bool,
+    conversions: FxHashMap<Box<str>, Box<str>>,
+    /// Number of chars of the longest conversion key
+    max_conversion_len: usize,
+}
+
+impl Normalizer {
+    /// Creates a normalizer that leaves the text unchanged
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables or disables folding full-width ASCII variants and the ideographic space
+    /// to their ASCII forms.
+    pub fn fold_width(mut self, enabled: bool) -> Self {
+        self.fold_width = enabled;
+        self
+    }
+
+    /// Enables or disables Unicode NFKC normalization.
+    ///
+    /// NFKC also folds full-width forms, as well as compatibility characters such as
+    /// `①` or `㍻`. Requires `nfkc` feature to be enabled.
+    #[cfg(feature = "nfkc")]
+    pub fn nfkc(mut self, enabled: bool) -> Self {
+        self.nfkc = enabled;
+        self
+    }
+
+    /// Adds a conversion from `from` to `to`, such as a traditional character or phrase to
+    /// its simplified form. The longest matching conversion is applied.
+    pub fn add_conversion(&mut self, from: &str, to: &str) {
+        if from.is_empty() {
+            return;
+        }
+        self.max_conversion_len = self.max_conversion_len.max(from.chars().count());
+        self.conversions.insert(from.into(), to.into());
+    }
+
+    /// Loads a conversion table, such as traditional to simplified Chinese.
+    ///
+    /// Each line holds a character or phrase and its conversion separated by whitespace,
+    /// in the format of the OpenCC dictionaries: `乾燥 干燥`. Further alternatives on a line
+    /// are ignored. Empty lines and lines starting with `#` are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jieba_rs::Normalizer;
+    ///
+    /// let mut normalizer = Normalizer::new();
+    /// normalizer.load_conversions(&mut "# traditional to simplified\n語\t语\n髮\t发 髮\n".as_bytes()).unwrap();
+    /// assert_eq!(normalizer.normalize("語言").as_str(), "语言");
+    /// ```
+    pub fn load_conversions<R: BufRead>(&mut self, reader: &mut R) -> Result<(), Error> {
+        let mut buf = String::new();
+        let mut line_no = 0;
+        while reader.read_line(&mut buf)? > 0 {
+            line_no += 1;
+            let line = buf.trim();
+            if !line.is_empty() && !line.starts_with('#') {
+                let mut parts = line.split_whitespace();
+                match (parts.next(), parts.next()) {
+                    (Some(from), Some(to)) => self.add_conversion(from, to),
+                    _ => {
+                        return Err(Error::InvalidConversionTable(format!(
+                            "line {line_no} `{line}` has no conversion"
+                        )));
+                    }
+                }
+            }
+            buf.clear();
+        }
+        Ok(())
+    }
+
+    /// Normalizes the text, keeping track of where each part of it comes from
+    pub fn normalize<'a>(&self, text: &'a str) -> Normalized<'a> {
+        let folded = self.fold(text);
+        if self.conversions.is_empty() {
+            return folded;
+        }
+
+        let mut normalized = Normalized::new(text);
+        let folded_text = folded.as_str();
+        let mut ends = Vec::with_capacity(self.max_conversion_len);
+        let mut rest = folded_text;
+        while !rest.is_empty() {
+            let offset = folded_text.len() - rest.len();
+            ends.clear();
+            ends.extend(
+                rest.char_indices()
+                    .map(|(i, c)| i + c.len_utf8())
+                    .take(self.max_conversion_len),
+            );
+            let conversion = ends
+                .iter()
+                .rev()
+                .find_map(|&len| self.conversions.get(&rest[..len]).map(|to| (len, to)));
+            let Some((len, to)) = conversion else {
+                normalized.push(&rest[..ends[0]], folded.locate(offset, false));
+                rest = &rest[ends[0]..];
+                continue;
+            };
+            let from = &rest[..len];
+            if from.chars().count() == to.chars().count() {
+                // Keep the positions of each char for conversions of the same length
+                for ((i, _), to) in from.char_indices().zip(to.chars()) {
+                    normalized.push(to.encode_utf8(&mut [0; 4]), folded.locate(offset + i, false));
+                }
+            } else {
+                normalized.push(to, folded.locate(offset, false));
+            }
+            rest = &rest[len..];
+        }
+        normalized.finish(folded.original_chars())
+    }
+
+    /// Applies width folding and NFKC, one char with its combining marks at a time
+    fn fold<'a>(&self, text: &'a str) -> Normalized<'a> {
+        let mut normalized = Normalized::new(text);
+        let mut chars = text.char_indices().peekable();
+        let mut char_offset = 0;
+        let mut buf = String::new();
+        while let Some((i, c)) = chars.next() {
+            let mut end = i + c.len_utf8();
+            let from = Piece {
+                byte: 0,
+                original_byte: i,
+                original_char: char_offset,
+            };
+            char_offset += 1;
+            // NFKC composes combining marks with the char before them
+            while let Some(&(j, mark)) = chars.peek()
+                && self.nfkc_enabled()
+                && is_mark(mark)
+            {
+                end = j + mark.len_utf8();
+                char_offset += 1;
+                chars.next();
+            }
+
+            buf.clear();
+            buf.extend(
+                text[i..end]
+                    .chars()
+                    .map(|ch| if self.fold_width { fold_width(ch) } else { ch }),
+            );
+            #[cfg(feature = "nfkc")]
+            if self.nfkc {
+                buf = buf.nfkc().collect();
+            }
+            normalized.push(&buf, from);
+        }
+        normalized.finish(char_offset)
+    }
+
+    fn nfkc_enabled(&self) -> bool {
+        #[cfg(feature = "nfkc")]
+        {
+            self.nfkc
+        }
+        #[cfg(not(feature = "nfkc"))]
+        {
+            false
+        }
+    }
+}
+
+#[cfg(feature = "nfkc")]
+fn is_mark(c: char) -> bool {
+    is_combining_mark(c)
+}
+
+#[cfg(not(feature = "nfkc"))]
+fn is_mark(_c: char) -> bool {
+    false
+}
+
+/// Folds full-width ASCII variants and the ideographic space
+fn fold_width(c: char) -> char {
+    match c {
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        '\u{3000}' => ' ',
+        _ => c,
+    }
+}
+
+/// A part of the normalized text and where it starts in the original input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Piece {
+    /// Byte start position in the normalized text
+    byte: usize,
+    /// Byte start position in the original input
+    original_byte: usize,
+    /// Unicode start position in the original input
+    original_char: usize,
+}
+
+/// Normalized text with the mapping of its positions to the original input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Normalized<'a> {
+    original: &'a str,
+    text: String,
+    /// Pieces of the normalized text in order, ending with the end of both texts
+    pieces: Vec<Piece>,
+}
+
+impl<'a> Normalized<'a> {
+    fn new(original: &'a str) -> Self {
+        Normalized {
+            original,
+            text: String::with_capacity(original.len()),
+            pieces: Vec::new(),
+        }
+    }
+
+    /// Appends `text` normalized from the original input starting at `from`. Parts
+    /// normalized from the same original position make up a single piece.
+    fn push(&mut self, text: &str, from: Piece) {
+        if self
+            .pieces
+            .last()
+            .is_none_or(|last| last.original_byte < from.original_byte)
+        {
+            self.pieces.push(Piece {
+                byte: self.text.len(),
+                ..from
+            });
+        }
+        self.text.push_str(text);
+    }
+
+    /// Ends the pieces with the end of both texts, `original_chars` being the number of
+    /// chars of the original input
+    fn finish(mut self, original_chars: usize) -> Self {
+        self.pieces.push(Piece {
+            byte: self.text.len(),
+            original_byte: self.original.len(),
+            original_char: original_chars,
+        });
+        self
+    }
+
+    fn original_chars(&self) -> usize {
+        self.pieces.last().map_or(0, |end| end.original_char)
+    }
+
+    /// The normalized text
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// The original input
+    pub fn original(&self) -> &'a str {
+        self.original
+    }
+
+    /// The piece starting at or containing `byte`, or the following one if `round_up`
+    /// and `byte` is inside the piece
+    fn locate(&self, byte: usize, round_up: bool) -> Piece {
+        let i = self.pieces.partition_point(|piece| piece.byte <= byte).max(1);
+        let piece = self.pieces[i - 1];
+        if round_up && piece.byte < byte {
+            self.pieces[i]
+        } else {
+            piece
+        }
+    }
+
+    /// Byte range of the original input the normalized byte range comes from
+    ///
+    /// A range starting or ending inside a part that normalization changed as a whole,
+    /// such as an expansion of NFKC, is widened to that part.
+    pub fn original_range(&self, byte_start: usize, byte_end: usize) -> Range<usize> {
+        self.locate(byte_start, false).original_byte..self.locate(byte_end, true).original_byte
+    }
+
+    /// Maps a token of the normalized text to the original input
+    pub(crate) fn map_token(&self, token: &Token<'_>) -> Token<'a> {
+        let start = self.locate(token.byte_start, false);
+        let end = self.locate(token.byte_end, true);
+        Token {
+            word: &self.original[start.original_byte..end.original_byte],
+            start: start.original_char,
+            end: end.original_char,
+            byte_start: start.original_byte,
+            byte_end: end.original_byte,
+        }
+    }
+
+    /// Maps the tokens of the normalized text to the original input, each paired with
+    /// the range of `tokens` it comes from.
+    ///
+    /// A token starting inside a part that normalization changed as a whole, such as the
+    /// NFKC expansion `平成` of `㍻` cut into several tokens, is merged into the token
+    /// right before it, so that both do not map to the same part of the original input.
+    pub(crate) fn map_tokens(&self, tokens: &[Token<'_>]) -> Vec<(Token<'a>, Range<usize>)> {
+        let mut mapped: Vec<(Token<'a>, Range<usize>)> = Vec::with_capacity(tokens.len());
+        for (i, token) in tokens.iter().enumerate() {
+            let inside = self.locate(token.byte_start, false).byte < token.byte_start;
+            let original = self.map_token(token);
+            match mapped.last_mut() {
+                Some((last, range)) if inside && tokens[range.end - 1].byte_end == token.byte_start => {
+                    last.byte_end = last.byte_end.max(original.byte_end);
+                    last.end = last.end.max(original.end);
+                    last.word = &self.original[last.byte_start..last.byte_end];
+                    range.end = i + 1;
+                }
+                _ => mapped.push((original, i..i + 1)),
+            }
+        }
+        mapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapped(normalized: &Normalized<'_>, words: &[&str]) -> Vec<(String, usize, usize)> {
+        let text = normalized.as_str();
+        let mut byte_start = 0;
+        words
+            .iter()
+            .map(|word| {
+                assert_eq!(&text[byte_start..byte_start + word.len()], *word);
+                let token = Token {
+                    word,
+                    start: 0,
+                    end: 0,
+                    byte_start,
+                    byte_end: byte_start + word.len(),
+                };
+                byte_start += word.len();
+                let token = normalized.map_token(&token);
+                (token.word.to_string(), token.start, token.end)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fold_width() {
+        let normalizer = Normalizer::new().fold_width(true);
+        let normalized = normalizer.normalize("ＡＢＣ１２３　中文");
+        assert_eq!(normalized.as_str(), "ABC123 中文");
+        assert_eq!(
+            mapped(&normalized, &["ABC", "123", " ", "中文"]),
+            vec![
+                ("ＡＢＣ".to_string(), 0, 3),
+                ("１２３".to_string(), 3, 6),
+                ("　".to_string(), 6, 7),
+                ("中文".to_string(), 7, 9)
+            ]
+        );
+        assert_eq!(Normalizer::new().normalize("ＡＢＣ").as_str(), "ＡＢＣ");
+    }
+
+    #[test]
+    fn test_conversions() {
+        let mut normalizer = Normalizer::new().fold_width(true);
+        normalizer
+            .load_conversions(&mut "#\n語\t语\n語言學 语言学\n資\t资\n乾燥 干燥\n".as_bytes())
+            .unwrap();
+        let normalized = normalizer.normalize("語言學資料ＸＹ");
+        assert_eq!(normalized.as_str(), "语言学资料XY");
+        assert_eq!(
+            mapped(&normalized, &["语言", "学", "资料", "XY"]),
+            vec![
+                ("語言".to_string(), 0, 2),
+                ("學".to_string(), 2, 3),
+                ("資料".to_string(), 3, 5),
+                ("ＸＹ".to_string(), 5, 7)
+            ]
+        );
+
+        let err = normalizer.load_conversions(&mut "語\n".as_bytes()).unwrap_err();
+        assert!(matches!(err, Error::InvalidConversionTable(_)));
+    }
+
+    #[cfg(feature = "nfkc")]
+    #[test]
+    fn test_nfkc() {
+        let normalizer = Normalizer::new().nfkc(true);
+        let normalized = normalizer.normalize("①e\u{301}ＡＢ");
+        assert_eq!(normalized.as_str(), "1éAB");
+        assert_eq!(
+            mapped(&normalized, &["1", "é", "AB"]),
+            vec![
+                ("①".to_string(), 0, 1),
+                ("e\u{301}".to_string(), 1, 3),
+                ("ＡＢ".to_string(), 3, 5)
+            ]
+        );
+    }
+    #[cfg(feature = "nfkc")]
+    #[test]
+    fn test_map_tokens_in_expansion() {
+        let normalizer = Normalizer::new().nfkc(true);
+        let normalized = normalizer.normalize("㍻元年ﬁx");
+        assert_eq!(normalized.as_str(), "平成元年fix");
+        let text = normalized.as_str();
+        let mut byte_start = 0;
+        let tokens: Vec<Token<'_>> = ["平", "成元", "年", "f", "ix"]
+            .iter()
+            .map(|word| {
+                let token = Token {
+                    word: &text[byte_start..byte_start + word.len()],
+                    start: 0,
+                    end: 0,
+                    byte_start,
+                    byte_end: byte_start + word.len(),
+                };
+                byte_start = token.byte_end;
+                token
+            })
+            .collect();
+        let mapped: Vec<_> = normalized
+            .map_tokens(&tokens)
+            .into_iter()
+            .map(|(token, range)| (token.word, token.start, token.end, range))
+            .collect();
+        assert_eq!(
+            mapped,
+            vec![("㍻元", 0, 2, 0..2), ("年", 2, 3, 2..3), ("ﬁx", 3, 5, 3..5)]
+        );
+    }
+}
@@ -0,0 +1,393 @@
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// Universal Dependencies part-of-speech tag (UPOS)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Upos {
+    /// Adjective
+    Adj,
+    /// Adposition
+    Adp,
+    /// Adverb
+    Adv,
+    /// Auxiliary
+    Aux,
+    /// Coordinating conjunction
+    Cconj,
+    /// Determiner
+    Det,
+    /// Interjection
+    Intj,
+    /// Noun
+    Noun,
+    /// Numeral
+    Num,
+    /// Particle
+    Part,
+    /// Pronoun
+    Pron,
+    /// Proper noun
+    Propn,
+    /// Punctuation
+    Punct,
+    /// Subordinating conjunction
+    Sconj,
+    /// Symbol
+    Sym,
+    /// Verb
+    Verb,
+    /// Other
+    X,
+}
+
+impl Upos {
+    /// The tag as written in Universal Dependencies, e.g. `PROPN`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Upos::Adj => "ADJ",
+            Upos::Adp => "ADP",
+            Upos::Adv => "ADV",
+            Upos::Aux => "AUX",
+            Upos::Cconj => "CCONJ",
+            Upos::Det => "DET",
+            Upos::Intj => "INTJ",
+            Upos::Noun => "NOUN",
+            Upos::Num => "NUM",
+            Upos::Part => "PART",
+            Upos::Pron => "PRON",
+            Upos::Propn => "PROPN",
+            Upos::Punct => "PUNCT",
+            Upos::Sconj => "SCONJ",
+            Upos::Sym => "SYM",
+            Upos::Verb => "VERB",
+            Upos::X => "X",
+        }
+    }
+}
+
+impl fmt::Display for Upos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Coarse part-of-speech category
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PosCategory {
+    /// Nouns, including proper nouns, place, time and locality words
+    Noun,
+    /// Verbs
+    Verb,
+    /// Adjectives, distinguishing and status words
+    Adjective,
+    /// Adverbs
+    Adverb,
+    /// Pronouns
+    Pronoun,
+    /// Numerals
+    Numeral,
+    /// Classifiers (measure words)
+    Classifier,
+    /// Prepositions
+    Preposition,
+    /// Conjunctions
+    Conjunction,
+    /// Auxiliary and modal particles
+    Particle,
+    /// Interjections and onomatopoeia
+    Interjection,
+    /// Idioms and fixed expressions
+    Idiom,
+    /// Punctuation
+    Punctuation,
+    /// Everything else: morphemes, affixes, non-morpheme strings and unknown tags
+    Other,
+}
+
+macro_rules! pos_tags {
+    ($($(#[$doc:meta])* $variant:ident => $tag:literal, $upos:ident, $category:ident;)*) => {
+        /// Part-of-speech tag of the ICTCLAS tag set used by jieba
+        ///
+        /// Covers the tags of the builtin POS tagging model and `eng`, with
+        /// [`PosTag::Other`] for any other tag found in a dictionary.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use jieba_rs::{PosCategory, PosTag, Upos};
+        ///
+        /// let tag: PosTag = "nrfg".parse().unwrap();
+        /// assert_eq!(tag, PosTag::Nrfg);
+        /// assert_eq!(tag.upos(), Upos::Propn);
+        /// assert_eq!(tag.category(), PosCategory::Noun);
+        /// assert_eq!(PosTag::from("custom"), PosTag::Other("custom".to_string()));
+        /// ```
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum PosTag {
+            $($(#[$doc])* $variant,)*
+            /// Any other tag
+            Other(String),
+        }
+
+        impl PosTag {
+            const KNOWN: &[PosTag] = &[$(PosTag::$variant),*];
+
+            /// The tag as written in dictionaries, e.g. `nrfg`
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(PosTag::$variant => $tag,)*
+                    PosTag::Other(tag) => tag,
+                }
+            }
+
+            /// Universal Dependencies UPOS tag, `X` for [`PosTag::Other`]
+            pub fn upos(&self) -> Upos {
+                match self {
+                    $(PosTag::$variant => Upos::$upos,)*
+                    PosTag::Other(_) => Upos::X,
+                }
+            }
+
+            /// Coarse category, `Other` for [`PosTag::Other`]
+            pub fn category(&self) -> PosCategory {
+                match self {
+                    $(PosTag::$variant => PosCategory::$category,)*
+                    PosTag::Other(_) => PosCategory::Other,
+                }
+            }
+
+            fn from_known(tag: &str) -> Option<PosTag> {
+                match tag {
+                    $($tag => Some(PosTag::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+pos_tags! {
+    /// Adjective (形容词)
+    A => "a", Adj, Adjective;
+    /// Adverbial adjective (副形词)
+    Ad => "ad", Adv, Adjective;
+    /// Adjective morpheme (形语素)
+    Ag => "ag", Adj, Adjective;
+    /// Nominal adjective (名形词)
+    An => "an", Noun, Adjective;
+    /// Distinguishing word (区别词)
+    B => "b", Adj, Adjective;
+    /// Distinguishing morpheme (区别语素)
+    Bg => "bg", Adj, Adjective;
+    /// Conjunction (连词)
+    C => "c", Cconj, Conjunction;
+    /// Adverb (副词)
+    D => "d", Adv, Adverb;
+    /// Negative adverb (不要)
+    Df => "df", Adv, Adverb;
+    /// Adverb morpheme (副语素)
+    Dg => "dg", Adv, Adverb;
+    /// Interjection (叹词)
+    E => "e", Intj, Interjection;
+    /// English-like token of the POS tagging model
+    En => "en", X, Other;
+    /// Locality word (方位词)
+    F => "f", Noun, Noun;
+    /// Morpheme (语素)
+    G => "g", X, Other;
+    /// Prefix (前缀)
+    H => "h", X, Other;
+    /// Idiom (成语)
+    I => "i", X, Idiom;
+    /// Nominal idiom
+    In => "in", X, Idiom;
+    /// Abbreviation (简称略语)
+    J => "j", Noun, Noun;
+    /// Nominal abbreviation
+    Jn => "jn", Noun, Noun;
+    /// Suffix (后缀)
+    K => "k", Part, Other;
+    /// Fixed expression (习用语)
+    L => "l", X, Idiom;
+    /// Nominal fixed expression
+    Ln => "ln", X, Idiom;
+    /// Numeral (数词)
+    M => "m", Num, Numeral;
+    /// Numeral morpheme (数语素)
+    Mg => "mg", Num, Numeral;
+    /// Numeral-classifier compound (数量词)
+    Mq => "mq", Num, Numeral;
+    /// Noun (名词)
+    N => "n", Noun, Noun;
+    /// Noun morpheme (名语素)
+    Ng => "ng", Noun, Noun;
+    /// Person name (人名)
+    Nr => "nr", Propn, Noun;
+    /// Person name, from a fine-grained tag set
+    Nrfg => "nrfg", Propn, Noun;
+    /// Transliterated person name (音译人名)
+    Nrt => "nrt", Propn, Noun;
+    /// Place name (地名)
+    Ns => "ns", Propn, Noun;
+    /// Organization name (机构团体)
+    Nt => "nt", Propn, Noun;
+    /// Other proper noun (其他专名)
+    Nz => "nz", Propn, Noun;
+    /// Onomatopoeia (拟声词)
+    O => "o", Intj, Interjection;
+    /// Preposition (介词)
+    P => "p", Adp, Preposition;
+    /// Classifier (量词)
+    Q => "q", Noun, Classifier;
+    /// Classifier, from a fine-grained tag set
+    Qe => "qe", Noun, Classifier;
+    /// Classifier morpheme
+    Qg => "qg", Noun, Classifier;
+    /// Pronoun (代词)
+    R => "r", Pron, Pronoun;
+    /// Pronoun morpheme (代语素)
+    Rg => "rg", Pron, Pronoun;
+    /// Personal pronoun (人称代词)
+    Rr => "rr", Pron, Pronoun;
+    /// Demonstrative pronoun (指示代词)
+    Rz => "rz", Pron, Pronoun;
+    /// Place word (处所词)
+    S => "s", Noun, Noun;
+    /// Time word (时间词)
+    T => "t", Noun, Noun;
+    /// Time morpheme (时语素)
+    Tg => "tg", Noun, Noun;
+    /// Auxiliary (助词)
+    U => "u", Part, Particle;
+    /// Auxiliary 得
+    Ud => "ud", Part, Particle;
+    /// Auxiliary 过
+    Ug => "ug", Part, Particle;
+    /// Auxiliary 的
+    Uj => "uj", Part, Particle;
+    /// Auxiliary 了
+    Ul => "ul", Part, Particle;
+    /// Auxiliary 地
+    Uv => "uv", Part, Particle;
+    /// Auxiliary 着
+    Uz => "uz", Part, Particle;
+    /// Verb (动词)
+    V => "v", Verb, Verb;
+    /// Adverbial verb (副动词)
+    Vd => "vd", Adv, Verb;
+    /// Verb morpheme (动语素)
+    Vg => "vg", Verb, Verb;
+    /// Intransitive verb (不及物动词)
+    Vi => "vi", Verb, Verb;
+    /// Nominal verb (名动词)
+    Vn => "vn", Noun, Verb;
+    /// Verb with a directional complement
+    Vq => "vq", Verb, Verb;
+    /// Punctuation (标点符号)
+    W => "w", Punct, Punctuation;
+    /// Non-morpheme string, also used by jieba for punctuation and unknown words (非语素字)
+    X => "x", X, Other;
+    /// Modal particle (语气词)
+    Y => "y", Part, Particle;
+    /// Modal particle morpheme (语气语素)
+    Yg => "yg", Part, Particle;
+    /// Status word (状态词)
+    Z => "z", Adj, Adjective;
+    /// Status word morpheme
+    Zg => "zg", Adj, Adjective;
+    /// English word, tagged by the ASCII heuristic of [`Jieba::tag`](crate::Jieba::tag)
+    Eng => "eng", X, Other;
+}
+
+impl PosTag {
+    /// Parse a tag, unknown tags become [`PosTag::Other`]
+    pub fn new(tag: &str) -> Self {
+        PosTag::from_known(tag).unwrap_or_else(|| PosTag::Other(tag.to_string()))
+    }
+
+    /// All tags except [`PosTag::Other`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jieba_rs::{PosCategory, PosTag};
+    ///
+    /// // Tags for `allowed_pos` in keyword extraction
+    /// let nouns: Vec<String> = PosTag::known()
+    ///     .filter(|tag| tag.category() == PosCategory::Noun)
+    ///     .map(String::from)
+    ///     .collect();
+    /// assert!(nouns.contains(&"ns".to_string()));
+    /// ```
+    pub fn known() -> impl Iterator<Item = PosTag> {
+        PosTag::KNOWN.iter().cloned()
+    }
+
+    /// Returns `true` for person, place, organization and other proper names
+    pub fn is_proper_noun(&self) -> bool {
+        self.upos() == Upos::Propn
+    }
+}
+
+impl fmt::Display for PosTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PosTag {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(PosTag::new(s))
+    }
+}
+
+impl From<&str> for PosTag {
+    fn from(tag: &str) -> Self {
+        PosTag::new(tag)
+    }
+}
+
+impl From<PosTag> for String {
+    fn from(tag: PosTag) -> Self {
+        match tag {
+            PosTag::Other(tag) => tag,
+            tag => tag.as_str().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_tags_roundtrip() {
+        assert_eq!(PosTag::known().count(), 65);
+        for tag in PosTag::known() {
+            assert_eq!(PosTag::new(tag.as_str()), tag);
+            assert_eq!(String::from(tag.clone()), tag.to_string());
+        }
+    }
+
+    #[cfg(feature = "default-dict")]
+    #[test]
+    fn test_covers_posseg_tags() {
+        for tag in crate::posseg::posseg_data().tags() {
+            assert!(!matches!(PosTag::new(tag), PosTag::Other(_)), "unknown tag {tag}");
+        }
+    }
+
+    #[test]
+    fn test_mappings() {
+        assert_eq!(PosTag::Ns.upos(), Upos::Propn);
+        assert_eq!(PosTag::Vn.upos(), Upos::Noun);
+        assert_eq!(PosTag::Vn.category(), PosCategory::Verb);
+        assert_eq!(PosTag::Uj.upos().to_string(), "PART");
+        assert!(PosTag::Nrt.is_proper_noun());
+        assert_eq!(PosTag::new("nrx"), PosTag::Other("nrx".to_string()));
+        assert_eq!(PosTag::new("nrx").upos(), Upos::X);
+    }
+}
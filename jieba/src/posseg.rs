@@ -1,53 +1,357 @@
 use std::cmp::Ordering;
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+#[cfg(feature = "default-dict")]
 use std::sync::OnceLock;
 
 use crate::FxHashMap;
+use crate::SplitByCharacterClass;
+use crate::dict_builder::parse_token;
+use crate::errors::Error;
+use crate::hmm::{cut_non_han, is_hmm_han};
 
 const MIN_FLOAT: f64 = -3.14e100;
 const NUM_POS: usize = 4; // B=0, M=1, E=2, S=3
-const NUM_TAGS: usize = 64;
-const NUM_STATES: usize = NUM_POS * NUM_TAGS;
+/// The dense transition matrix grows with the square of the number of tags, 8 MB at this bound.
+const MAX_TAGS: usize = 256;
+/// Number of tags of the embedded model, decoded with constant state index arithmetic.
+const BUILTIN_NUM_TAGS: usize = 64;
 
-#[inline]
-fn state_idx(pos: usize, tag: usize) -> usize {
-    pos * NUM_TAGS + tag
+/// A runtime-loadable POS tagging model.
+///
+/// The model is a joint HMM over word position (Begin/Middle/End/Single) and
+/// POS tag states, used to tag out-of-vocabulary words. It replaces the
+/// compile-time embedded `posseg.txt` model when set with
+/// [`Jieba::set_posseg_model`](crate::Jieba::set_posseg_model).
+#[derive(Clone, PartialEq)]
+pub struct PossegModel {
+    tags: Vec<Box<str>>,
+    start_prob: Vec<f64>,
+    /// Dense row-major transition matrix. trans_prob[from * num_states + to] = log-prob.
+    trans_prob: Vec<f64>,
+    emit_prob: Vec<FxHashMap<char, f64>>,
+    char_state_tab: FxHashMap<char, Vec<u16>>,
+    /// All possible state indices (used as fallback when char is not in char_state_tab).
+    all_states: Vec<u16>,
 }
 
-#[inline]
-fn state_pos(idx: usize) -> usize {
-    idx / NUM_TAGS
+impl fmt::Debug for PossegModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PossegModel")
+            .field("tags", &self.tags)
+            .field("chars_len", &self.char_state_tab.len())
+            .finish()
+    }
 }
 
-#[inline]
-fn state_tag(idx: usize) -> usize {
-    idx % NUM_TAGS
+impl PossegModel {
+    /// Creates a model over `tags` with every probability set to `MIN_FLOAT`.
+    ///
+    /// Tags must be non-empty and free of `,` and whitespace, so that [`PossegModel::save`]
+    /// can write them, and there can be at most `MAX_TAGS` of them.
+    fn with_tags(tags: Vec<Box<str>>) -> Result<Self, String> {
+        if tags.len() > MAX_TAGS {
+            return Err(format!(
+                "too many POS tags: {}, at most {MAX_TAGS} are supported",
+                tags.len()
+            ));
+        }
+        if let Some(tag) = tags
+            .iter()
+            .find(|tag| tag.is_empty() || tag.contains(|c: char| c == ',' || c.is_whitespace()))
+        {
+            return Err(format!("invalid POS tag `{tag}`"));
+        }
+        let num_states = NUM_POS * tags.len();
+        Ok(PossegModel {
+            tags,
+            start_prob: vec![MIN_FLOAT; num_states],
+            trans_prob: vec![MIN_FLOAT; num_states * num_states],
+            emit_prob: vec![FxHashMap::default(); num_states],
+            char_state_tab: FxHashMap::default(),
+            all_states: (0..num_states as u16).collect(),
+        })
+    }
+
+    /// Load a POS tagging model from a reader in the `posseg.txt` file format.
+    ///
+    /// The file consists of the `@TAGS`, `@START`, `@TRANS`, `@EMIT` and
+    /// `@CHAR_STATE` sections written by [`PossegModel::save`] and
+    /// `scripts/convert_posseg.py`. The `@TAGS` section must come first, with at
+    /// most 256 tags.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use std::io::BufReader;
+    /// use std::fs::File;
+    /// use jieba_rs::{Jieba, PossegModel};
+    ///
+    /// let mut jieba = Jieba::new();
+    /// let mut f = BufReader::new(File::open("my_posseg.txt").unwrap());
+    /// let model = PossegModel::load(&mut f).unwrap();
+    /// jieba.set_posseg_model(model);
+    /// ```
+    pub fn load<R: BufRead>(reader: &mut R) -> Result<Self, Error> {
+        let mut data = String::new();
+        reader.read_to_string(&mut data)?;
+        parse_posseg_data(&data)
+    }
+
+    /// Train a POS tagging model from a tagged corpus.
+    ///
+    /// Each sentence contains `word/tag` tokens separated by whitespace, as in the
+    /// People's Daily corpus; untagged words are tagged `x`. Every character is
+    /// labeled with its position in the word (Begin/Middle/End/Single) joined with
+    /// the word tag, then the start, transition and emission log-probabilities and
+    /// the states seen for each character are estimated from the counts.
+    ///
+    /// The tag set of the model is the sorted set of tags of the corpus, at most 256
+    /// tags without `,`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use jieba_rs::{Jieba, PossegModel};
+    ///
+    /// let corpus = ["我/r 爱/v 北京/ns 天安门/ns", "张三/nr 去/v 上海/ns"];
+    /// let model = PossegModel::train(corpus).unwrap();
+    /// assert_eq!(model.tags().collect::<Vec<_>>(), vec!["nr", "ns", "r", "v"]);
+    ///
+    /// let mut jieba = Jieba::empty();
+    /// jieba.set_posseg_model(model);
+    /// ```
+    pub fn train<I, S>(sentences: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        // (position, tag id in order of appearance)
+        type State = (usize, usize);
+
+        let mut tag_ids: FxHashMap<String, usize> = FxHashMap::default();
+        let mut start_count: FxHashMap<State, u64> = FxHashMap::default();
+        let mut trans_count: FxHashMap<(State, State), u64> = FxHashMap::default();
+        let mut emit_count: FxHashMap<State, FxHashMap<char, u64>> = FxHashMap::default();
+        let mut char_states: FxHashMap<char, Vec<State>> = FxHashMap::default();
+
+        for sentence in sentences {
+            let mut prev: Option<State> = None;
+            for (word, tag) in sentence.as_ref().split_whitespace().filter_map(parse_token) {
+                let tag = if tag.is_empty() { "x" } else { tag };
+                let next_id = tag_ids.len();
+                let tag_id = match tag_ids.get(tag) {
+                    Some(&id) => id,
+                    None => *tag_ids.entry(tag.to_string()).or_insert(next_id),
+                };
+                let char_len = word.chars().count();
+                for (i, ch) in word.chars().enumerate() {
+                    let pos = if char_len == 1 {
+                        3
+                    } else if i == 0 {
+                        0
+                    } else if i == char_len - 1 {
+                        2
+                    } else {
+                        1
+                    };
+                    let state = (pos, tag_id);
+                    match prev {
+                        None => *start_count.entry(state).or_insert(0) += 1,
+                        Some(p) => *trans_count.entry((p, state)).or_insert(0) += 1,
+                    }
+                    *emit_count.entry(state).or_default().entry(ch).or_insert(0) += 1;
+                    let states = char_states.entry(ch).or_default();
+                    if !states.contains(&state) {
+                        states.push(state);
+                    }
+                    prev = Some(state);
+                }
+            }
+        }
+
+        let total_start: u64 = start_count.values().sum();
+        if total_start == 0 {
+            return Err(Error::InvalidPossegModel("empty training corpus".to_string()));
+        }
+
+        // Renumber the tags in sorted order
+        let mut tags: Vec<(String, usize)> = tag_ids.into_iter().collect();
+        tags.sort_unstable();
+        let mut tag_map = vec![0; tags.len()];
+        for (new_id, (_, old_id)) in tags.iter().enumerate() {
+            tag_map[*old_id] = new_id;
+        }
+        let mut model = PossegModel::with_tags(tags.into_iter().map(|(tag, _)| tag.into()).collect())
+            .map_err(Error::InvalidPossegModel)?;
+        let num_tags = model.tags.len();
+        let num_states = model.num_states();
+        let state_idx = |(pos, tag): State| pos * num_tags + tag_map[tag];
+        let log_prob = |count: u64, total: u64| (count as f64 / total as f64).ln();
+
+        for (state, count) in start_count {
+            model.start_prob[state_idx(state)] = log_prob(count, total_start);
+        }
+
+        let mut trans_total: FxHashMap<State, u64> = FxHashMap::default();
+        for (&(from, _), &count) in &trans_count {
+            *trans_total.entry(from).or_insert(0) += count;
+        }
+        for ((from, to), count) in trans_count {
+            model.trans_prob[state_idx(from) * num_states + state_idx(to)] = log_prob(count, trans_total[&from]);
+        }
+
+        for (state, counts) in emit_count {
+            let total: u64 = counts.values().sum();
+            model.emit_prob[state_idx(state)] = counts
+                .into_iter()
+                .map(|(ch, count)| (ch, log_prob(count, total)))
+                .collect();
+        }
+
+        for (ch, states) in char_states {
+            let mut states: Vec<u16> = states.into_iter().map(|s| state_idx(s) as u16).collect();
+            states.sort_unstable();
+            model.char_state_tab.insert(ch, states);
+        }
+
+        Ok(model)
+    }
+
+    /// Save the model in the `posseg.txt` file format read by [`PossegModel::load`].
+    ///
+    /// Probabilities of `MIN_FLOAT` are omitted. Characters the format cannot
+    /// represent (`|`, whitespace and `@` as a `@CHAR_STATE` key) are skipped.
+    pub fn save<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        let representable = |ch: char| ch != '|' && !ch.is_whitespace();
+        let num_states = self.num_states();
+
+        writeln!(
+            writer,
+            "# {} POS tags, 4 position tags (B=0 M=1 E=2 S=3)",
+            self.tags.len()
+        )?;
+        writeln!(writer, "@TAGS")?;
+        writeln!(writer, "{}", self.tags.join(","))?;
+
+        writeln!(writer, "@START")?;
+        for (state, &prob) in self.start_prob.iter().enumerate() {
+            if prob > MIN_FLOAT {
+                writeln!(writer, "{},{},{prob:?}", self.state_pos(state), self.state_tag(state))?;
+            }
+        }
+
+        writeln!(writer, "@TRANS")?;
+        for from in 0..num_states {
+            let row = &self.trans_prob[from * num_states..(from + 1) * num_states];
+            if row.iter().all(|&prob| prob <= MIN_FLOAT) {
+                continue;
+            }
+            write!(writer, "{},{}", self.state_pos(from), self.state_tag(from))?;
+            for (to, &prob) in row.iter().enumerate() {
+                if prob > MIN_FLOAT {
+                    write!(writer, "|{},{},{prob:?}", self.state_pos(to), self.state_tag(to))?;
+                }
+            }
+            writeln!(writer)?;
+        }
+
+        writeln!(writer, "@EMIT")?;
+        for (state, emit) in self.emit_prob.iter().enumerate() {
+            let mut chars: Vec<(char, f64)> = emit
+                .iter()
+                .map(|(&ch, &prob)| (ch, prob))
+                .filter(|&(ch, _)| representable(ch))
+                .collect();
+            if chars.is_empty() {
+                continue;
+            }
+            chars.sort_unstable_by_key(|&(ch, _)| ch);
+            write!(writer, "{},{}", self.state_pos(state), self.state_tag(state))?;
+            for (ch, prob) in chars {
+                write!(writer, "|{ch},{prob:?}")?;
+            }
+            writeln!(writer)?;
+        }
+
+        writeln!(writer, "@CHAR_STATE")?;
+        let mut chars: Vec<(char, &[u16])> = self
+            .char_state_tab
+            .iter()
+            .map(|(&ch, states)| (ch, states.as_slice()))
+            .filter(|&(ch, _)| representable(ch) && ch != '@')
+            .collect();
+        chars.sort_unstable_by_key(|&(ch, _)| ch);
+        for (ch, states) in chars {
+            write!(writer, "{ch}")?;
+            for &state in states {
+                let state = state as usize;
+                write!(writer, "|{},{}", self.state_pos(state), self.state_tag(state))?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    /// The POS tags of the model, indexed by tag id.
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().map(|t| &**t)
+    }
+
+    #[inline]
+    fn num_states(&self) -> usize {
+        NUM_POS * self.tags.len()
+    }
+
+    #[inline]
+    fn state_idx(&self, pos: usize, tag: usize) -> usize {
+        pos * self.tags.len() + tag
+    }
+
+    #[inline]
+    fn state_pos(&self, idx: usize) -> usize {
+        idx / self.tags.len()
+    }
+
+    #[inline]
+    fn state_tag(&self, idx: usize) -> usize {
+        idx % self.tags.len()
+    }
 }
 
-pub(crate) struct PossegData {
-    tags: Vec<Box<str>>,
-    start_prob: [f64; NUM_STATES],
-    /// Dense 256×256 transition matrix. trans_prob[from][to] = log-prob.
-    trans_prob: Box<[[f64; NUM_STATES]; NUM_STATES]>,
-    emit_prob: Vec<FxHashMap<char, f64>>,
-    char_state_tab: FxHashMap<char, Vec<u16>>,
+/// Parse a `pos,tag` state pair of `model`.
+fn parse_state<'a>(model: &PossegModel, parts: &mut impl Iterator<Item = &'a str>) -> Result<usize, String> {
+    let pos = parse_field::<usize>(parts.next(), "position")?;
+    let tag = parse_field::<usize>(parts.next(), "tag")?;
+    if pos >= NUM_POS {
+        return Err(format!("position {pos} out of range"));
+    }
+    if tag >= model.tags.len() {
+        return Err(format!("tag {tag} out of range"));
+    }
+    Ok(model.state_idx(pos, tag))
 }
 
-fn parse_posseg_data(data: &str) -> PossegData {
-    let mut tags: Vec<Box<str>> = Vec::new();
-    let mut start_prob = [MIN_FLOAT; NUM_STATES];
-    // Initialize dense matrix to MIN_FLOAT
-    let trans_prob = vec![[MIN_FLOAT; NUM_STATES]; NUM_STATES].into_boxed_slice();
-    let trans_prob: Box<[[f64; NUM_STATES]; NUM_STATES]> = unsafe {
-        let ptr = Box::into_raw(trans_prob) as *mut [[f64; NUM_STATES]; NUM_STATES];
-        Box::from_raw(ptr)
-    };
-    let mut trans_prob = trans_prob;
-    let mut emit_prob: Vec<FxHashMap<char, f64>> = vec![FxHashMap::default(); NUM_STATES];
-    let mut char_state_tab: FxHashMap<char, Vec<u16>> = FxHashMap::default();
+fn parse_field<T: FromStr>(field: Option<&str>, name: &str) -> Result<T, String>
+where
+    T::Err: fmt::Display,
+{
+    let field = field.ok_or_else(|| format!("missing {name}"))?.trim();
+    field.parse().map_err(|e| format!("invalid {name} `{field}`: {e}"))
+}
+
+fn parse_char(field: Option<&str>) -> Result<char, String> {
+    field
+        .and_then(|s| s.chars().next())
+        .ok_or_else(|| "missing character".to_string())
+}
 
+fn parse_posseg_data(data: &str) -> Result<PossegModel, Error> {
+    let mut model: Option<PossegModel> = None;
     let mut section = "";
 
-    for line in data.lines() {
+    for (line_no, line) in data.lines().enumerate() {
         let line = line.trim();
         if line.is_empty() {
             continue;
@@ -64,105 +368,90 @@ fn parse_posseg_data(data: &str) -> PossegData {
             continue;
         }
 
-        match section {
-            "tags" => {
-                tags = line.split(',').map(|s| s.trim().into()).collect();
-                assert_eq!(tags.len(), NUM_TAGS, "expected {NUM_TAGS} POS tags, got {}", tags.len());
+        let mut parse_line = || -> Result<(), String> {
+            if section == "tags" {
+                if model.is_some() {
+                    return Err("duplicate @TAGS section".to_string());
+                }
+                let tags: Vec<Box<str>> = line.split(',').map(|s| s.trim().into()).collect();
+                model = Some(PossegModel::with_tags(tags)?);
+                return Ok(());
             }
-            "start" => {
-                let mut parts = line.splitn(3, ',');
-                let pos: usize = parts.next().unwrap().parse().unwrap();
-                let tag: usize = parts.next().unwrap().parse().unwrap();
-                let prob: f64 = parts.next().unwrap().parse().unwrap();
-                start_prob[state_idx(pos, tag)] = prob;
+            if section.is_empty() {
+                return Ok(());
             }
-            "trans" => {
-                let mut segments = line.split('|');
-                let from_part = segments.next().unwrap();
-                let mut from_iter = from_part.splitn(2, ',');
-                let from_pos: usize = from_iter.next().unwrap().parse().unwrap();
-                let from_tag: usize = from_iter.next().unwrap().parse().unwrap();
-                let from = state_idx(from_pos, from_tag);
-
-                for seg in segments {
-                    let seg = seg.trim();
-                    if seg.is_empty() {
-                        continue;
+            let model = model.as_mut().ok_or_else(|| "section before @TAGS".to_string())?;
+            let num_states = model.num_states();
+            match section {
+                "start" => {
+                    let mut parts = line.splitn(3, ',');
+                    let state = parse_state(model, &mut parts)?;
+                    model.start_prob[state] = parse_field(parts.next(), "probability")?;
+                }
+                "trans" => {
+                    let mut segments = line.split('|');
+                    let from = parse_state(model, &mut segments.next().unwrap_or("").splitn(2, ','))?;
+
+                    for seg in segments {
+                        let seg = seg.trim();
+                        if seg.is_empty() {
+                            continue;
+                        }
+                        let mut parts = seg.splitn(3, ',');
+                        let to = parse_state(model, &mut parts)?;
+                        model.trans_prob[from * num_states + to] = parse_field(parts.next(), "probability")?;
                     }
-                    let mut parts = seg.splitn(3, ',');
-                    let to_pos: usize = parts.next().unwrap().parse().unwrap();
-                    let to_tag: usize = parts.next().unwrap().parse().unwrap();
-                    let prob: f64 = parts.next().unwrap().parse().unwrap();
-                    trans_prob[from][state_idx(to_pos, to_tag)] = prob;
                 }
-            }
-            "emit" => {
-                let mut segments = line.split('|');
-                let state_part = segments.next().unwrap();
-                let mut state_iter = state_part.splitn(2, ',');
-                let pos: usize = state_iter.next().unwrap().parse().unwrap();
-                let tag: usize = state_iter.next().unwrap().parse().unwrap();
-                let si = state_idx(pos, tag);
-
-                for seg in segments {
-                    let seg = seg.trim();
-                    if seg.is_empty() {
-                        continue;
+                "emit" => {
+                    let mut segments = line.split('|');
+                    let state = parse_state(model, &mut segments.next().unwrap_or("").splitn(2, ','))?;
+
+                    for seg in segments {
+                        let seg = seg.trim();
+                        if seg.is_empty() {
+                            continue;
+                        }
+                        let mut parts = seg.rsplitn(2, ',');
+                        let prob = parse_field(parts.next(), "probability")?;
+                        let ch = parse_char(parts.next())?;
+                        model.emit_prob[state].insert(ch, prob);
                     }
-                    let mut parts = seg.rsplitn(2, ',');
-                    let prob: f64 = parts.next().unwrap().parse().unwrap();
-                    let ch_str = parts.next().unwrap();
-                    let ch = ch_str.chars().next().unwrap();
-                    emit_prob[si].insert(ch, prob);
                 }
-            }
-            "char_state" => {
-                let mut segments = line.split('|');
-                let ch_str = segments.next().unwrap();
-                let ch = ch_str.chars().next().unwrap();
-                let mut states = Vec::new();
-                for seg in segments {
-                    let seg = seg.trim();
-                    if seg.is_empty() {
-                        continue;
+                "char_state" => {
+                    let mut segments = line.split('|');
+                    let ch = parse_char(segments.next())?;
+                    let mut states = Vec::new();
+                    for seg in segments {
+                        let seg = seg.trim();
+                        if seg.is_empty() {
+                            continue;
+                        }
+                        states.push(parse_state(model, &mut seg.splitn(2, ','))? as u16);
                     }
-                    let mut parts = seg.splitn(2, ',');
-                    let pos: usize = parts.next().unwrap().parse().unwrap();
-                    let tag: usize = parts.next().unwrap().parse().unwrap();
-                    states.push(state_idx(pos, tag) as u16);
+                    model.char_state_tab.insert(ch, states);
                 }
-                char_state_tab.insert(ch, states);
+                _ => {}
             }
-            _ => {}
-        }
+            Ok(())
+        };
+        parse_line().map_err(|e| Error::InvalidPossegModel(format!("line {}: {e}", line_no + 1)))?;
     }
 
-    PossegData {
-        tags,
-        start_prob,
-        trans_prob,
-        emit_prob,
-        char_state_tab,
-    }
+    model.ok_or_else(|| Error::InvalidPossegModel("missing @TAGS section".to_string()))
 }
 
 #[cfg(feature = "default-dict")]
 include_flate::flate!(static POSSEG_DATA: str from "src/data/posseg.txt");
 
 #[cfg(feature = "default-dict")]
-static POSSEG: OnceLock<PossegData> = OnceLock::new();
+static POSSEG: OnceLock<PossegModel> = OnceLock::new();
 
 #[cfg(feature = "default-dict")]
-pub(crate) fn posseg_data() -> &'static PossegData {
-    POSSEG.get_or_init(|| parse_posseg_data(&POSSEG_DATA))
+pub(crate) fn posseg_data() -> &'static PossegModel {
+    POSSEG.get_or_init(|| parse_posseg_data(&POSSEG_DATA).expect("invalid embedded posseg data"))
 }
 
-/// All possible state indices (used as fallback when char is not in char_state_tab).
-fn all_states() -> Vec<u16> {
-    (0..NUM_STATES as u16).collect()
-}
-
-impl PossegData {
+impl PossegModel {
     fn get_char_states(&self, ch: char) -> &[u16] {
         self.char_state_tab.get(&ch).map(|v| v.as_slice()).unwrap_or(&[])
     }
@@ -176,20 +465,86 @@ impl PossegData {
     }
 }
 
-fn viterbi_posseg<'a>(data: &'a PossegData, chars: &[(usize, char)]) -> Vec<(usize, usize, &'a str)> {
+/// Log-probability margin of `state` over the best word-ending (E or S) state with another tag.
+///
+/// `scores` are the Viterbi scores of all states at one timestep. Returns `f64::INFINITY`
+/// when no other tag is reachable.
+fn tag_margin(data: &PossegModel, scores: &[f64], state: usize) -> f64 {
+    let tag = data.state_tag(state);
+    let runner_up = scores
+        .iter()
+        .enumerate()
+        .filter(|&(s, _)| matches!(data.state_pos(s), 2 | 3) && data.state_tag(s) != tag)
+        .map(|(_, &score)| score)
+        .fold(MIN_FLOAT, f64::max);
+    if runner_up <= MIN_FLOAT {
+        f64::INFINITY
+    } else {
+        scores[state] - runner_up
+    }
+}
+
+/// A tag guessed by the compound HMM
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PosGuess<'b> {
+    pub(crate) tag: &'b str,
+    /// Whether the word is an `x` fallback for a run the model could not decode
+    pub(crate) fallback: bool,
+    /// Margin of the tag as computed by [`tag_margin`], only if asked for and not a fallback
+    pub(crate) margin: Option<f64>,
+}
+
+impl PosGuess<'_> {
+    const FALLBACK: PosGuess<'static> = PosGuess {
+        tag: "x",
+        fallback: true,
+        margin: None,
+    };
+}
+
+/// Run the compound HMM over `chars`, returning `(byte_start, byte_end, guess)` words.
+///
+/// If `margins` is true, the guesses of decoded words have the [`tag_margin`] of the word
+/// at its last character. This keeps the Viterbi scores of every timestep, so it is only
+/// done when the margins are reported.
+fn viterbi_posseg<'a>(
+    data: &'a PossegModel,
+    chars: &[(usize, char)],
+    margins: bool,
+) -> Vec<(usize, usize, PosGuess<'a>)> {
+    if data.tags.len() == BUILTIN_NUM_TAGS {
+        viterbi_posseg_with::<BUILTIN_NUM_TAGS>(data, chars, margins)
+    } else {
+        viterbi_posseg_with::<0>(data, chars, margins)
+    }
+}
+
+/// [`viterbi_posseg`] for models of `TAGS` tags, or any number of tags if `TAGS` is 0.
+///
+/// A constant number of tags turns the state index arithmetic of the hot loop into
+/// constant multiplications and divisions.
+fn viterbi_posseg_with<'a, const TAGS: usize>(
+    data: &'a PossegModel,
+    chars: &[(usize, char)],
+    margins: bool,
+) -> Vec<(usize, usize, PosGuess<'a>)> {
     let c_len = chars.len();
     if c_len == 0 {
         return Vec::new();
     }
 
+    let num_tags = if TAGS == 0 { data.tags.len() } else { TAGS };
+    let state_pos = |state: usize| state / num_tags;
+    let state_tag = |state: usize| state % num_tags;
     let str_end = chars[c_len - 1].0 + chars[c_len - 1].1.len_utf8();
-    let fallback = all_states();
+    let num_states = NUM_POS * num_tags;
+    let fallback = &data.all_states;
 
     // Single character: just pick the best S state
     if c_len == 1 {
         let ch = chars[0].1;
         let candidates = data.get_char_states(ch);
-        let candidates = if candidates.is_empty() { &fallback } else { candidates };
+        let candidates = if candidates.is_empty() { fallback } else { candidates };
         let best = candidates
             .iter()
             .filter(|&&s| state_pos(s as usize) == 3) // S states only
@@ -199,24 +554,36 @@ fn viterbi_posseg<'a>(data: &'a PossegData, chars: &[(usize, char)]) -> Vec<(usi
             })
             .filter(|(p, _)| *p > MIN_FLOAT)
             .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
-        let tag = match best {
-            Some((_, s)) => data.tag_str(state_tag(s as usize)),
-            None => "x",
+        let guess = match best {
+            Some((_, best_state)) => PosGuess {
+                tag: data.tag_str(state_tag(best_state as usize)),
+                fallback: false,
+                margin: margins.then(|| {
+                    let mut scores = vec![MIN_FLOAT; num_states];
+                    for &s in candidates.iter().filter(|&&s| state_pos(s as usize) == 3) {
+                        scores[s as usize] = data.start_prob[s as usize] + data.emit(s as usize, ch);
+                    }
+                    tag_margin(data, &scores, best_state as usize)
+                }),
+            },
+            None => PosGuess::FALLBACK,
         };
-        return vec![(chars[0].0, str_end, tag)];
+        return vec![(chars[0].0, str_end, guess)];
     }
 
     // Rolling score buffers: only need prev and current rows
-    let mut prev_scores = [MIN_FLOAT; NUM_STATES];
-    let mut cur_scores = [MIN_FLOAT; NUM_STATES];
-    // Backpointer table: still need full c_len × NUM_STATES for traceback
-    let mut prev = vec![u16::MAX; c_len * NUM_STATES];
+    let mut prev_scores = vec![MIN_FLOAT; num_states];
+    let mut cur_scores = vec![MIN_FLOAT; num_states];
+    // Backpointer table: still need full c_len × num_states for traceback
+    let mut prev = vec![u16::MAX; c_len * num_states];
+    // Full c_len × num_states score table, only kept to compute the margins
+    let mut history = Vec::new();
 
     // Initialize t=0
     let first_ch = chars[0].1;
     let first_states = data.get_char_states(first_ch);
     let first_states = if first_states.is_empty() {
-        &fallback
+        fallback
     } else {
         first_states
     };
@@ -224,17 +591,21 @@ fn viterbi_posseg<'a>(data: &'a PossegData, chars: &[(usize, char)]) -> Vec<(usi
         let si = s as usize;
         prev_scores[si] = data.start_prob[si] + data.emit(si, first_ch);
     }
+    if margins {
+        history.reserve(c_len * num_states);
+        history.extend_from_slice(&prev_scores);
+    }
 
     // Recurse
     for t in 1..c_len {
         let ch = chars[t].1;
         let cur_states = data.get_char_states(ch);
-        let cur_states = if cur_states.is_empty() { &fallback } else { cur_states };
+        let cur_states = if cur_states.is_empty() { fallback } else { cur_states };
 
         // Hoist prev_states lookup outside the inner loop
         let prev_ch = chars[t - 1].1;
         let prev_states = data.get_char_states(prev_ch);
-        let prev_states = if prev_states.is_empty() { &fallback } else { prev_states };
+        let prev_states = if prev_states.is_empty() { fallback } else { prev_states };
 
         cur_scores.fill(MIN_FLOAT);
 
@@ -251,7 +622,7 @@ fn viterbi_posseg<'a>(data: &'a PossegData, chars: &[(usize, char)]) -> Vec<(usi
                     continue;
                 }
                 // O(1) dense matrix lookup
-                let tp = data.trans_prob[psi][si];
+                let tp = data.trans_prob[psi * num_states + si];
                 if tp <= MIN_FLOAT {
                     continue;
                 }
@@ -263,37 +634,40 @@ fn viterbi_posseg<'a>(data: &'a PossegData, chars: &[(usize, char)]) -> Vec<(usi
             }
 
             cur_scores[si] = best_prob;
-            prev[t * NUM_STATES + si] = best_prev;
+            prev[t * num_states + si] = best_prev;
         }
 
-        prev_scores = cur_scores;
+        std::mem::swap(&mut prev_scores, &mut cur_scores);
+        if margins {
+            history.extend_from_slice(&prev_scores);
+        }
     }
 
     // Terminate: find best E or S state at the last timestep
     let last_t = c_len - 1;
     let mut best_prob = MIN_FLOAT;
     let mut best_state = u16::MAX;
-    for s in 0..NUM_STATES {
+    for (s, &score) in prev_scores.iter().enumerate() {
         let pos = state_pos(s);
-        if (pos == 2 || pos == 3) && prev_scores[s] > best_prob {
-            best_prob = prev_scores[s];
+        if (pos == 2 || pos == 3) && score > best_prob {
+            best_prob = score;
             best_state = s as u16;
         }
     }
 
     // Fallback if no valid E/S state was reachable
     if best_state == u16::MAX || best_prob <= MIN_FLOAT {
-        return vec![(chars[0].0, str_end, "x")];
+        return vec![(chars[0].0, str_end, PosGuess::FALLBACK)];
     }
 
     // Traceback
     let mut path = vec![0u16; c_len];
     path[last_t] = best_state;
     for t in (1..c_len).rev() {
-        let backptr = prev[t * NUM_STATES + path[t] as usize];
+        let backptr = prev[t * num_states + path[t] as usize];
         if backptr == u16::MAX {
             // Unreachable path — return whole span as fallback
-            return vec![(chars[0].0, str_end, "x")];
+            return vec![(chars[0].0, str_end, PosGuess::FALLBACK)];
         }
         path[t - 1] = backptr;
     }
@@ -313,17 +687,18 @@ fn viterbi_posseg<'a>(data: &'a PossegData, chars: &[(usize, char)]) -> Vec<(usi
             1 => {
                 // M: middle, do nothing
             }
-            2 => {
-                // E: end of word
-                let byte_end = if t + 1 < c_len { chars[t + 1].0 } else { str_end };
-                let tag = data.tag_str(state_tag(s));
-                result.push((word_start, byte_end, tag));
-            }
-            3 => {
-                // S: single char word
+            2 | 3 => {
+                // E: end of word, S: single char word
+                if pos == 3 {
+                    word_start = chars[t].0;
+                }
                 let byte_end = if t + 1 < c_len { chars[t + 1].0 } else { str_end };
-                let tag = data.tag_str(state_tag(s));
-                result.push((chars[t].0, byte_end, tag));
+                let guess = PosGuess {
+                    tag: data.tag_str(state_tag(s)),
+                    fallback: false,
+                    margin: margins.then(|| tag_margin(data, &history[t * num_states..(t + 1) * num_states], s)),
+                };
+                result.push((word_start, byte_end, guess));
             }
             _ => unreachable!(),
         }
@@ -331,14 +706,14 @@ fn viterbi_posseg<'a>(data: &'a PossegData, chars: &[(usize, char)]) -> Vec<(usi
 
     // Fallback if decoding produced no words (e.g. all B/M with no E)
     if result.is_empty() {
-        return vec![(chars[0].0, str_end, "x")];
+        return vec![(chars[0].0, str_end, PosGuess::FALLBACK)];
     }
 
     // Handle incomplete B..M sequence at end
-    if let Some(&(_, byte_end, _)) = result.last() {
-        if byte_end < str_end {
-            result.push((byte_end, str_end, "x"));
-        }
+    if let Some(&(_, byte_end, _)) = result.last()
+        && byte_end < str_end
+    {
+        result.push((byte_end, str_end, PosGuess::FALLBACK));
     }
 
     result
@@ -346,54 +721,94 @@ fn viterbi_posseg<'a>(data: &'a PossegData, chars: &[(usize, char)]) -> Vec<(usi
 
 /// Segment and POS-tag a Chinese character string using the compound HMM.
 ///
-/// Returns `(word_slice, pos_tag_str)` pairs where `pos_tag_str` has `'static`
-/// lifetime because it references the lazily-initialized static data.
-#[cfg(feature = "default-dict")]
-pub(crate) fn cut_with_pos(sentence: &str) -> Vec<(&str, &'static str)> {
-    let data = posseg_data();
+/// Returns the words with the tags guessed by the model, with their margins if `margins`
+/// is true.
+pub(crate) fn cut_with_pos_margins<'a, 'b>(
+    data: &'b PossegModel,
+    sentence: &'a str,
+    margins: bool,
+) -> Vec<(&'a str, PosGuess<'b>)> {
     let chars: Vec<(usize, char)> = sentence.char_indices().collect();
-    if chars.is_empty() {
-        return Vec::new();
-    }
-
-    let spans = viterbi_posseg(data, &chars);
-    spans
+    viterbi_posseg(data, &chars, margins)
         .into_iter()
-        .map(|(start, end, tag)| (&sentence[start..end], tag))
+        .map(|(start, end, guess)| (&sentence[start..end], guess))
         .collect()
 }
 
-#[cfg(all(test, feature = "default-dict"))]
+/// Segment and POS-tag an out-of-vocabulary run, like `__cut_detail` of Python jieba.
+///
+/// Han blocks are segmented and tagged together by the compound HMM, with the tag
+/// margins of [`cut_with_pos_margins`] if `margins` is true. Other blocks are split like
+/// the segmentation HMM does and left untagged.
+pub(crate) fn cut_detail<'a, 'b>(
+    data: &'b PossegModel,
+    sentence: &'a str,
+    margins: bool,
+    words: &mut Vec<(&'a str, Option<PosGuess<'b>>)>,
+) {
+    let mut untagged = Vec::new();
+    for state in SplitByCharacterClass::new(sentence, is_hmm_han) {
+        let block = state.as_str();
+        if block.is_empty() {
+            continue;
+        }
+        if state.is_matched() {
+            words.extend(
+                cut_with_pos_margins(data, block, margins)
+                    .into_iter()
+                    .map(|(word, guess)| (word, Some(guess))),
+            );
+        } else {
+            untagged.clear();
+            cut_non_han(block, &mut untagged);
+            words.extend(untagged.iter().map(|&word| (word, None)));
+        }
+    }
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "default-dict")]
     use expect_test::expect;
 
+    fn cut_with_pos<'a, 'b>(data: &'b PossegModel, sentence: &'a str) -> Vec<(&'a str, &'b str)> {
+        cut_with_pos_margins(data, sentence, false)
+            .into_iter()
+            .map(|(word, guess)| (word, guess.tag))
+            .collect()
+    }
+
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_posseg_basic() {
-        let results = cut_with_pos("我来到北京清华大学");
+        let results = cut_with_pos(posseg_data(), "我来到北京清华大学");
         let formatted: Vec<String> = results.iter().map(|(w, t)| format!("{}/{}", w, t)).collect();
         expect![[r#"["我/r", "来/v", "到/v", "北京/ns", "清华大学/nt"]"#]].assert_eq(&format!("{:?}", formatted));
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_posseg_person_name() {
-        let results = cut_with_pos("小明硕士毕业于中国科学院计算所");
+        let results = cut_with_pos(posseg_data(), "小明硕士毕业于中国科学院计算所");
         let formatted: Vec<String> = results.iter().map(|(w, t)| format!("{}/{}", w, t)).collect();
         expect![[r#"["小明/nr", "硕士/n", "毕业/n", "于/p", "中国科学院/nt", "计算/v", "所/u"]"#]]
             .assert_eq(&format!("{:?}", formatted));
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_posseg_single_char() {
-        let results = cut_with_pos("我");
+        let results = cut_with_pos(posseg_data(), "我");
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].0, "我");
         assert_eq!(results[0].1, "r"); // pronoun
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_posseg_oov_name() {
-        let results = cut_with_pos("张尧");
+        let results = cut_with_pos(posseg_data(), "张尧");
         let formatted: Vec<String> = results.iter().map(|(w, t)| format!("{}/{}", w, t)).collect();
         assert!(
             results.iter().any(|(_, t)| *t == "nr"),
@@ -402,9 +817,76 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "default-dict")]
     #[test]
     fn test_posseg_empty() {
-        let results = cut_with_pos("");
+        let results = cut_with_pos(posseg_data(), "");
         assert!(results.is_empty());
     }
+
+    #[cfg(feature = "default-dict")]
+    #[test]
+    fn test_load_posseg_model() {
+        let model = PossegModel::load(&mut POSSEG_DATA.as_bytes()).unwrap();
+        assert_eq!(model.tags().count(), 64);
+        let results = cut_with_pos(&model, "我来到北京清华大学");
+        assert_eq!(results, cut_with_pos(posseg_data(), "我来到北京清华大学"));
+    }
+
+    #[test]
+    fn test_load_invalid_posseg_model() {
+        let err = PossegModel::load(&mut "@TAGS\na,b\n@START\n0,1\n".as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "invalid POS model: line 4: missing probability");
+
+        let err = PossegModel::load(&mut "@TAGS\na,b\n@TRANS\n0,1|1,2,-1.0\n".as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "invalid POS model: line 4: tag 2 out of range");
+
+        let err = PossegModel::load(&mut "@START\n0,1,-1.0\n".as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "invalid POS model: line 2: section before @TAGS");
+
+        let err = PossegModel::load(&mut "@EMIT\n".as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "invalid POS model: missing @TAGS section");
+
+        let tags = (0..=MAX_TAGS).map(|i| format!("t{i}")).collect::<Vec<_>>().join(",");
+        let err = PossegModel::load(&mut format!("@TAGS\n{tags}\n").as_bytes()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid POS model: line 2: too many POS tags: 257, at most 256 are supported"
+        );
+
+        let err = PossegModel::load(&mut "@TAGS\na,,b\n".as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "invalid POS model: line 2: invalid POS tag ``");
+    }
+
+    #[cfg(feature = "default-dict")]
+    #[test]
+    fn test_save_load_roundtrip() {
+        let mut saved = Vec::new();
+        posseg_data().save(&mut saved).unwrap();
+        let loaded = PossegModel::load(&mut saved.as_slice()).unwrap();
+        assert!(loaded == *posseg_data());
+    }
+
+    #[test]
+    fn test_train_posseg_model() {
+        let corpus = [
+            "我/r 爱/v 北京/ns 天安门/ns",
+            "[中国/ns 政府/n]nt 在/p 北京/ns",
+            "张三/nr 来到/v 北京/ns",
+            "李四/nr 来到/v 上海/ns",
+        ];
+        let model = PossegModel::train(corpus).unwrap();
+        assert_eq!(model.tags().collect::<Vec<_>>(), vec!["n", "nr", "ns", "p", "r", "v"]);
+        assert_eq!(
+            cut_with_pos(&model, "张三来到上海"),
+            vec![("张三", "nr"), ("来到", "v"), ("上海", "ns")]
+        );
+
+        let mut saved = Vec::new();
+        model.save(&mut saved).unwrap();
+        let loaded = PossegModel::load(&mut saved.as_slice()).unwrap();
+        assert!(loaded == model);
+
+        assert!(PossegModel::train([""]).is_err());
+    }
 }
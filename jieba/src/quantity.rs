@@ -0,0 +1,441 @@
+use crate::{PosCategory, Tag};
+
+/// Kind of a quantity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuantityKind {
+    /// A plain number, such as 三千五百万元
+    Cardinal,
+    /// An ordinal number, such as 第十二
+    Ordinal,
+    /// A percentage or permillage, such as 百分之二十 or 20%
+    Percent,
+}
+
+/// A number with its unit or measure word
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity<'a> {
+    /// Text of the quantity
+    pub text: &'a str,
+    /// Numeric value, `20.0` for 百分之二十
+    pub value: f64,
+    /// Unit or measure word, such as `元` or `个`, `%` or `‰` for percentages, may be empty
+    pub unit: &'a str,
+    /// Kind of the quantity
+    pub kind: QuantityKind,
+    /// Unicode start position of the quantity in the original input
+    pub start: usize,
+    /// Unicode end position of the quantity in the original input
+    pub end: usize,
+    /// Byte start position of the quantity in the original input
+    pub byte_start: usize,
+    /// Byte end position of the quantity in the original input
+    pub byte_end: usize,
+}
+
+/// Units that are tagged as nouns rather than measure words
+const UNITS: &[&str] = &[
+    "元",
+    "块",
+    "角",
+    "美元",
+    "欧元",
+    "日元",
+    "英镑",
+    "港元",
+    "港币",
+    "人民币",
+    "%",
+    "％",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Numeral {
+    Digit(f64),
+    /// 十, 百 and 千
+    SmallUnit(f64),
+    /// 万 and 亿
+    BigUnit(f64),
+}
+
+fn numeral(c: char) -> Option<Numeral> {
+    let numeral = match c {
+        '零' | '〇' => Numeral::Digit(0.0),
+        '一' | '壹' | '幺' => Numeral::Digit(1.0),
+        '二' | '贰' | '两' => Numeral::Digit(2.0),
+        '三' | '叁' => Numeral::Digit(3.0),
+        '四' | '肆' => Numeral::Digit(4.0),
+        '五' | '伍' => Numeral::Digit(5.0),
+        '六' | '陆' => Numeral::Digit(6.0),
+        '七' | '柒' => Numeral::Digit(7.0),
+        '八' | '捌' => Numeral::Digit(8.0),
+        '九' | '玖' => Numeral::Digit(9.0),
+        '十' | '拾' => Numeral::SmallUnit(10.0),
+        '百' | '佰' => Numeral::SmallUnit(100.0),
+        '千' | '仟' => Numeral::SmallUnit(1000.0),
+        '万' | '萬' => Numeral::BigUnit(1e4),
+        '亿' | '億' => Numeral::BigUnit(1e8),
+        _ => return None,
+    };
+    Some(numeral)
+}
+
+/// ASCII or full-width digit
+fn digit(c: char) -> Option<u32> {
+    match c {
+        '0'..='9' => c.to_digit(10),
+        '０'..='９' => Some(c as u32 - '０' as u32),
+        _ => None,
+    }
+}
+
+fn is_decimal_point(c: char) -> bool {
+    matches!(c, '.' | '．')
+}
+
+/// Byte length of the number at the start of `text`, 0 if there is none.
+///
+/// A number is a run of digits and Chinese numerals with an optional leading `-` or `负`
+/// and decimal part, such as `-4.3亿` or `三点五`.
+pub(crate) fn number_len(text: &str) -> usize {
+    let mut chars = text.char_indices().peekable();
+    if let Some(&(_, '-' | '负')) = chars.peek() {
+        chars.next();
+    }
+    let mut end = 0;
+    while let Some((i, c)) = chars.next() {
+        let next_is_digit = chars.peek().is_some_and(|&(_, n)| digit(n).is_some());
+        let next_is_numeral = chars
+            .peek()
+            .is_some_and(|&(_, n)| matches!(numeral(n), Some(Numeral::Digit(_))));
+        if digit(c).is_some() || numeral(c).is_some() {
+            end = i + c.len_utf8();
+        } else if (is_decimal_point(c) && end > 0 && next_is_digit) || (c == '点' && end > 0 && next_is_numeral) {
+            // decimal part, consumed with the following digits
+        } else {
+            break;
+        }
+    }
+    end
+}
+
+/// Byte length of the run of digits and Chinese numerals at the start of `text`,
+/// without sign or decimal part, such as `十二` in `十二点半`.
+pub(crate) fn integer_len(text: &str) -> usize {
+    text.char_indices()
+        .find(|&(_, c)| digit(c).is_none() && numeral(c).is_none())
+        .map_or(text.len(), |(i, _)| i)
+}
+
+/// Whether the integer is written digit by digit, such as `2013` or `二〇一三` but not `十二`
+pub(crate) fn is_digit_by_digit(text: &str) -> bool {
+    text.chars()
+        .all(|c| digit(c).is_some() || matches!(numeral(c), Some(Numeral::Digit(_))))
+}
+
+/// Parse a number written with digits, Chinese numerals or both, such as `4.3亿`,
+/// `三千五百万`, `十二`, `二〇一三`, `三点五` or `三点五亿`.
+pub(crate) fn parse_number(text: &str) -> Option<f64> {
+    let (negative, text) = match text.strip_prefix('-').or_else(|| text.strip_prefix('负')) {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    if text.is_empty() {
+        return None;
+    }
+
+    // Digits after 点 are read one by one: 三点一四 is 3.14, and may be followed by
+    // 万, 亿 or 万亿: 三点五亿 is 3.5e8
+    let (integer, fraction) = match text.split_once('点') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (text, None),
+    };
+    let mut value = parse_integer(integer)?;
+    if let Some(fraction) = fraction {
+        let mut scale = 0.1;
+        let mut multiplier = 1.0;
+        for c in fraction.chars() {
+            match numeral(c) {
+                Some(Numeral::BigUnit(unit)) => multiplier *= unit,
+                _ if multiplier > 1.0 => return None,
+                Some(Numeral::Digit(d)) => {
+                    value += d * scale;
+                    scale /= 10.0;
+                }
+                _ => {
+                    value += digit(c)? as f64 * scale;
+                    scale /= 10.0;
+                }
+            }
+        }
+        value *= multiplier;
+    }
+    Some(if negative { -value } else { value })
+}
+
+fn parse_integer(text: &str) -> Option<f64> {
+    let has_unit = text
+        .chars()
+        .any(|c| matches!(numeral(c), Some(Numeral::SmallUnit(_) | Numeral::BigUnit(_))));
+    if !has_unit {
+        // Digit by digit, such as 2013, 二〇一三 or 4.3
+        let mut literal = String::with_capacity(text.len());
+        for c in text.chars() {
+            match (digit(c), numeral(c)) {
+                (Some(d), _) => literal.push(char::from_digit(d, 10)?),
+                (None, Some(Numeral::Digit(d))) => literal.push(char::from_digit(d as u32, 10)?),
+                _ if is_decimal_point(c) => literal.push('.'),
+                _ => return None,
+            }
+        }
+        return literal.parse().ok();
+    }
+
+    let mut total = 0.0;
+    let mut section = 0.0;
+    let mut number: Option<f64> = None;
+    // Unit right before the last digit, for the elided 三百五 (350)
+    let mut last_unit: Option<f64> = None;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let literal_len = rest
+            .char_indices()
+            .find(|&(_, c)| digit(c).is_none() && !is_decimal_point(c))
+            .map_or(rest.len(), |(i, _)| i);
+        if literal_len > 0 {
+            number = Some(parse_integer(&rest[..literal_len])?);
+            rest = &rest[literal_len..];
+            continue;
+        }
+        let c = rest.chars().next()?;
+        rest = &rest[c.len_utf8()..];
+        match numeral(c)? {
+            Numeral::Digit(d) => {
+                if d == 0.0 {
+                    last_unit = None;
+                }
+                number = Some(d);
+            }
+            Numeral::SmallUnit(unit) => {
+                section += number.take().unwrap_or(1.0) * unit;
+                last_unit = Some(unit);
+            }
+            Numeral::BigUnit(unit) => {
+                let value = section + number.take().unwrap_or(0.0);
+                if value == 0.0 {
+                    total = if total == 0.0 { unit } else { total * unit };
+                } else {
+                    total += value * unit;
+                }
+                section = 0.0;
+                last_unit = Some(unit);
+            }
+        }
+    }
+    if let Some(n) = number {
+        // 三百五 is 350 and 一万五 is 15000, unless a 零 came in between
+        match last_unit {
+            Some(unit) if unit >= 100.0 && n < 10.0 => section += n * unit / 10.0,
+            _ => section += n,
+        }
+    }
+    Some(total + section)
+}
+
+/// Whether the token starts a quantity
+fn starts_quantity(tag: &Tag<'_>) -> bool {
+    let word = tag.word;
+    let prefixed = word
+        .strip_prefix('第')
+        .or_else(|| word.strip_prefix("百分之"))
+        .or_else(|| word.strip_prefix("千分之"));
+    match prefixed {
+        Some("") => true,
+        Some(rest) => number_len(rest) > 0,
+        None => {
+            let starts_with_digit = word.chars().next().is_some_and(|c| digit(c).is_some());
+            starts_with_digit || (tag.pos().category() == PosCategory::Numeral && number_len(word) > 0)
+        }
+    }
+}
+
+/// Whether the token is the unit of a preceding number
+fn is_unit(tag: &Tag<'_>) -> bool {
+    UNITS.contains(&tag.word)
+        || matches!(tag.pos().category(), PosCategory::Classifier | PosCategory::Numeral) && number_len(tag.word) == 0
+}
+
+impl<'a> Quantity<'a> {
+    /// Extracts the quantities of `sentence` from its tags.
+    ///
+    /// `tags` are the output of [`Jieba::tag`](crate::Jieba::tag) for `sentence`. A number
+    /// written with digits, Chinese numerals or both is merged with the following unit or
+    /// measure word into one quantity, whether they are cut into one token or several.
+    /// `第` makes an ordinal, and `百分之`, `千分之` or `%` a percentage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jieba_rs::{Jieba, Quantity, QuantityKind};
+    ///
+    /// let mut jieba = Jieba::empty();
+    /// jieba.add_word("三千五百万", Some(100), Some("m"));
+    /// jieba.add_word("元", Some(100), Some("m"));
+    ///
+    /// let sentence = "共三千五百万元";
+    /// let tags = jieba.tag(sentence, false);
+    /// let quantities = Quantity::from_tags(sentence, &tags);
+    /// assert_eq!(quantities[0].text, "三千五百万元");
+    /// assert_eq!(quantities[0].value, 35_000_000.0);
+    /// assert_eq!(quantities[0].unit, "元");
+    /// assert_eq!(quantities[0].kind, QuantityKind::Cardinal);
+    /// ```
+    pub fn from_tags(sentence: &'a str, tags: &[Tag<'a>]) -> Vec<Quantity<'a>> {
+        let mut quantities = Vec::new();
+        let mut i = 0;
+        while i < tags.len() {
+            let tag = &tags[i];
+            let negative = (tag.word == "-" || tag.word == "负") && tags.get(i + 1).is_some_and(starts_quantity);
+            if !negative && !starts_quantity(tag) {
+                i += 1;
+                continue;
+            }
+            let byte_start = tag.byte_start;
+            let mut rest = &sentence[byte_start..];
+            let mut kind = QuantityKind::Cardinal;
+            let mut unit = "";
+            if let Some(after) = rest.strip_prefix('第') {
+                kind = QuantityKind::Ordinal;
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix("百分之") {
+                (kind, unit) = (QuantityKind::Percent, "%");
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix("千分之") {
+                (kind, unit) = (QuantityKind::Percent, "‰");
+                rest = after;
+            }
+            let number_start = sentence.len() - rest.len();
+            let number_len = number_len(rest);
+            let Some(value) = parse_number(&rest[..number_len]) else {
+                i += 1;
+                continue;
+            };
+            let number_end = number_start + number_len;
+
+            // Last token of the number, the rest of it is the unit
+            let mut j = i;
+            while tags[j].byte_end < number_end && j + 1 < tags.len() {
+                j += 1;
+            }
+            if tags[j].byte_end > number_end {
+                unit = &sentence[number_end..tags[j].byte_end];
+            } else if let Some(next) = tags.get(j + 1)
+                && unit.is_empty()
+                && is_unit(next)
+            {
+                unit = next.word;
+                j += 1;
+            }
+            if kind == QuantityKind::Cardinal && matches!(unit, "%" | "％") {
+                kind = QuantityKind::Percent;
+            }
+
+            let last = &tags[j];
+            quantities.push(Quantity {
+                text: &sentence[byte_start..last.byte_end],
+                value,
+                unit,
+                kind,
+                start: tag.start,
+                end: last.end,
+                byte_start,
+                byte_end: last.byte_end,
+            });
+            i = j + 1;
+        }
+        quantities
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quantities<'a>(sentence: &'a str, words: &[(&'a str, &'a str)]) -> Vec<(&'a str, f64, &'a str, QuantityKind)> {
+        let tags = Tag::from_pairs(words.iter().copied());
+        assert_eq!(tags.iter().map(|t| t.word).collect::<String>(), sentence);
+        Quantity::from_tags(sentence, &tags)
+            .into_iter()
+            .map(|q| (q.text, q.value, q.unit, q.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_number() {
+        assert_eq!(parse_number("三千五百万"), Some(35_000_000.0));
+        assert_eq!(parse_number("4.3亿"), Some(430_000_000.0));
+        assert_eq!(parse_number("十二"), Some(12.0));
+        assert_eq!(parse_number("二十"), Some(20.0));
+        assert_eq!(parse_number("一百零五"), Some(105.0));
+        assert_eq!(parse_number("三百五"), Some(350.0));
+        assert_eq!(parse_number("一万五"), Some(15_000.0));
+        assert_eq!(parse_number("一万亿"), Some(1e12));
+        assert_eq!(parse_number("三亿五千万"), Some(350_000_000.0));
+        assert_eq!(parse_number("二〇一三"), Some(2013.0));
+        assert_eq!(parse_number("三点二五"), Some(3.25));
+        assert_eq!(parse_number("三点五亿"), Some(350_000_000.0));
+        assert_eq!(parse_number("一点二万亿"), Some(1.2e12));
+        assert_eq!(parse_number("1.5万"), Some(15_000.0));
+        assert_eq!(parse_number("三点五亿二"), None);
+        assert_eq!(parse_number("-139.13"), Some(-139.13));
+        assert_eq!(parse_number("７０００万"), Some(70_000_000.0));
+        assert_eq!(parse_number("元"), None);
+    }
+
+    #[test]
+    fn test_number_len() {
+        assert_eq!(number_len("4.3亿元"), "4.3亿".len());
+        assert_eq!(number_len("三点五倍"), "三点五".len());
+        assert_eq!(number_len("三点钟"), "三".len());
+        assert_eq!(number_len("三点五亿元"), "三点五亿".len());
+        assert_eq!(number_len("元"), 0);
+    }
+
+    #[test]
+    fn test_quantities() {
+        assert_eq!(
+            quantities(
+                "增资4.3亿元，",
+                &[("增资", "v"), ("4.3", "m"), ("亿元", "m"), ("，", "x")]
+            ),
+            vec![("4.3亿元", 430_000_000.0, "元", QuantityKind::Cardinal)]
+        );
+        assert_eq!(
+            quantities("百分之二十", &[("百分之", "m"), ("二十", "m")]),
+            vec![("百分之二十", 20.0, "%", QuantityKind::Percent)]
+        );
+        assert_eq!(
+            quantities("第十二届", &[("第十二", "m"), ("届", "q")]),
+            vec![("第十二届", 12.0, "届", QuantityKind::Ordinal)]
+        );
+        assert_eq!(
+            quantities(
+                "净利润-139.13万元",
+                &[("净利润", "n"), ("-", "x"), ("139.13", "m"), ("万元", "m")]
+            ),
+            vec![("-139.13万元", -1_391_300.0, "元", QuantityKind::Cardinal)]
+        );
+        assert_eq!(
+            quantities("增长20%", &[("增长", "v"), ("20", "m"), ("%", "x")]),
+            vec![("20%", 20.0, "%", QuantityKind::Percent)]
+        );
+        assert_eq!(
+            quantities("三点五亿元", &[("三点五亿", "m"), ("元", "m")]),
+            vec![("三点五亿元", 350_000_000.0, "元", QuantityKind::Cardinal)]
+        );
+        assert_eq!(
+            quantities("1.5万人", &[("1.5", "m"), ("万人", "m")]),
+            vec![("1.5万人", 15_000.0, "人", QuantityKind::Cardinal)]
+        );
+        assert!(quantities("一起", &[("一起", "d")]).is_empty());
+    }
+}
@@ -0,0 +1,324 @@
+use std::cmp::Reverse;
+
+use regex::Regex;
+
+use crate::char_count;
+use crate::errors::Error;
+
+/// Kind of a span recognized before segmentation
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PatternKind {
+    /// `http`, `https` and `ftp` URLs, and URLs starting with `www.`
+    Url,
+    /// Email addresses
+    Email,
+    /// `@` mentions, such as `@张三`
+    Mention,
+    /// `#话题#` and `#topic` hashtags
+    Hashtag,
+    /// Western emoticons such as `:)` and `^_^`
+    Emoticon,
+    /// IPv4 addresses with an optional port, and IPv6 addresses
+    Ip,
+    /// Version numbers such as `v1.2` and `1.2.3-beta`
+    Version,
+    /// User pattern with its name
+    Custom(Box<str>),
+}
+
+impl PatternKind {
+    /// Built-in kinds, in the order they are tried
+    pub const BUILTIN: [PatternKind; 7] = [
+        PatternKind::Url,
+        PatternKind::Email,
+        PatternKind::Mention,
+        PatternKind::Hashtag,
+        PatternKind::Ip,
+        PatternKind::Version,
+        PatternKind::Emoticon,
+    ];
+
+    /// Label of the kind, used as the tag of the recognized words: `url`, `email`, `mention`,
+    /// `hashtag`, `emoticon`, `ip`, `version` or the name of a user pattern
+    pub fn as_str(&self) -> &str {
+        match self {
+            PatternKind::Url => "url",
+            PatternKind::Email => "email",
+            PatternKind::Mention => "mention",
+            PatternKind::Hashtag => "hashtag",
+            PatternKind::Emoticon => "emoticon",
+            PatternKind::Ip => "ip",
+            PatternKind::Version => "version",
+            PatternKind::Custom(name) => name,
+        }
+    }
+
+    fn builtin_pattern(&self) -> Option<&'static str> {
+        let pattern = match self {
+            PatternKind::Url => {
+                r"(?i:(?:https?|ftp)://|www\.)[A-Za-z0-9\-._~:/?#\[\]@!$&'()*+,;=%]*[A-Za-z0-9/_\-#=&%~+]"
+            }
+            PatternKind::Email => r"[A-Za-z0-9._%+\-]+@[A-Za-z0-9\-]+(?:\.[A-Za-z0-9\-]+)*\.[A-Za-z]{2,}",
+            PatternKind::Mention => r"@[\p{Han}A-Za-z0-9_\-]+",
+            PatternKind::Hashtag => r"#[^#\s]{1,50}#|#[\p{Han}A-Za-z0-9_]+",
+            PatternKind::Emoticon => r"[:;=][\-o']?[)\](\[DPpO/\\|]|\^_*\^|[Tt]_[Tt]|<3",
+            PatternKind::Ip => concat!(
+                r"(?:(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\.){3}(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)(?::\d{1,5})?",
+                r"|(?:[0-9A-Fa-f]{1,4}:){7}[0-9A-Fa-f]{1,4}",
+                r"|(?:[0-9A-Fa-f]{1,4}(?::[0-9A-Fa-f]{1,4})*)?::(?:[0-9A-Fa-f]{1,4}(?::[0-9A-Fa-f]{1,4})*)?",
+            ),
+            PatternKind::Version => r"[vV]\d+(?:\.\d+)+(?:-[0-9A-Za-z.]+)?|\d+\.\d+\.\d+(?:\.\d+)*(?:-[0-9A-Za-z.]+)?",
+            PatternKind::Custom(_) => return None,
+        };
+        Some(pattern)
+    }
+
+    /// Whether a match of the built-in pattern is kept, rejecting the look-alikes that the
+    /// regex cannot tell apart
+    fn accepts(&self, text: &str, start: usize, end: usize) -> bool {
+        let matched = &text[start..end];
+        match self {
+            // A compressed IPv6 address has a digit, unlike `::` or `a::b` in prose
+            PatternKind::Ip => !matched.contains("::") || matched.bytes().any(|b| b.is_ascii_digit()),
+            // 2024.1.15 is a date
+            PatternKind::Version => !is_date(matched),
+            // The colon of 选项:D is punctuation
+            PatternKind::Emoticon => {
+                !(matched.starts_with([':', ';', '=']) && text[..start].chars().next_back().is_some_and(crate::is_cjk))
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Whether the text is a date such as `2024.1.15`
+fn is_date(text: &str) -> bool {
+    let parts: Vec<&str> = text.split('.').collect();
+    parts.len() == 3
+        && parts.iter().all(|part| part.bytes().all(|b| b.is_ascii_digit()))
+        && parts[0].len() == 4
+        && parts[1..].iter().all(|part| (1..=2).contains(&part.len()))
+}
+
+/// A span of text recognized by a [`Recognizer`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Recognized<'a> {
+    /// Recognized text
+    pub text: &'a str,
+    /// Kind of the pattern matching the text
+    pub kind: PatternKind,
+    /// Unicode start position of the text
+    pub start: usize,
+    /// Unicode end position of the text
+    pub end: usize,
+    /// Byte start position of the text
+    pub byte_start: usize,
+    /// Byte end position of the text
+    pub byte_end: usize,
+}
+
+/// Patterns recognized before segmentation, such as URLs, emails and hashtags
+///
+/// Set on a [`Jieba`](crate::Jieba) instance with
+/// [`Jieba::set_recognizer`](crate::Jieba::set_recognizer), each recognized span is emitted as
+/// a single token, tagged with the label of its [`PatternKind`]. The text around it is
+/// segmented as usual.
+///
+/// When several patterns match, the leftmost match wins, then the longest one, then the
+/// pattern added first. Matches of the built-in patterns must not be preceded or followed
+/// by an ASCII letter or digit, so that `C#` is not a hashtag. Dates such as `2024.1.15`
+/// are not versions, compressed IPv6 addresses need a digit, and emoticons starting with
+/// `:`, `;` or `=` are not recognized right after a CJK character, as in `选项:D`.
+///
+/// # Examples
+///
+/// ```
+/// use jieba_rs::{PatternKind, Recognizer};
+///
+/// let recognizer = Recognizer::builtin();
+/// let spans = recognizer.find("@张三 看 https://example.com/a?b=1 #话题# v1.2.3");
+/// let kinds: Vec<(&str, &PatternKind)> = spans.iter().map(|s| (s.text, &s.kind)).collect();
+/// assert_eq!(
+///     kinds,
+///     vec![
+///         ("@张三", &PatternKind::Mention),
+///         ("https://example.com/a?b=1", &PatternKind::Url),
+///         ("#话题#", &PatternKind::Hashtag),
+///         ("v1.2.3", &PatternKind::Version),
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Recognizer {
+    patterns: Vec<(PatternKind, Regex)>,
+}
+
+impl Recognizer {
+    /// Creates a recognizer without patterns
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a recognizer with all the built-in patterns
+    pub fn builtin() -> Self {
+        PatternKind::BUILTIN
+            .into_iter()
+            .fold(Self::new(), |recognizer, kind| recognizer.with_builtin(kind))
+    }
+
+    /// Adds the built-in pattern of `kind`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `kind` is [`PatternKind::Custom`], add user patterns with
+    /// [`Recognizer::add_pattern`] instead.
+    pub fn with_builtin(mut self, kind: PatternKind) -> Self {
+        let pattern = kind
+            .builtin_pattern()
+            .expect("custom patterns are added with Recognizer::add_pattern");
+        let regex = Regex::new(pattern).expect("built-in patterns are valid");
+        self.patterns.push((kind, regex));
+        self
+    }
+
+    /// Adds a user regex `pattern`, recognized as [`PatternKind::Custom`] with the given name
+    pub fn add_pattern(&mut self, name: &str, pattern: &str) -> Result<(), Error> {
+        let regex = Regex::new(pattern)?;
+        self.patterns.push((PatternKind::Custom(name.into()), regex));
+        Ok(())
+    }
+
+    /// Whether the recognizer has no patterns
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Find the recognized spans of `text`, in order and without overlap
+    pub fn find<'a>(&self, text: &'a str) -> Vec<Recognized<'a>> {
+        let mut unicode_offset = 0;
+        let mut last_end = 0;
+        self.spans(text)
+            .into_iter()
+            .map(|(byte_start, byte_end, kind)| {
+                let start = unicode_offset + char_count(&text[last_end..byte_start]);
+                let end = start + char_count(&text[byte_start..byte_end]);
+                unicode_offset = end;
+                last_end = byte_end;
+                Recognized {
+                    text: &text[byte_start..byte_end],
+                    kind: kind.clone(),
+                    start,
+                    end,
+                    byte_start,
+                    byte_end,
+                }
+            })
+            .collect()
+    }
+
+    /// Byte spans of the recognized text with their kinds, in order and without overlap
+    pub(crate) fn spans(&self, text: &str) -> Vec<(usize, usize, &PatternKind)> {
+        let mut matches = Vec::new();
+        for (index, (kind, regex)) in self.patterns.iter().enumerate() {
+            let builtin = !matches!(kind, PatternKind::Custom(_));
+            let mut pos = 0;
+            while pos <= text.len()
+                && let Some(m) = regex.find_at(text, pos)
+            {
+                if !m.is_empty()
+                    && (!builtin || is_delimited(text, m.start(), m.end()) && kind.accepts(text, m.start(), m.end()))
+                {
+                    matches.push((m.start(), m.end(), index));
+                    pos = m.end();
+                } else {
+                    // Retry from the next char, a rejected match may hide a shorter one
+                    pos = m.start() + text[m.start()..].chars().next().map_or(1, char::len_utf8);
+                }
+            }
+        }
+        matches.sort_by_key(|&(start, end, index)| (start, Reverse(end), index));
+
+        let mut spans = Vec::new();
+        let mut last_end = 0;
+        for (start, end, index) in matches {
+            if start >= last_end {
+                spans.push((start, end, &self.patterns[index].0));
+                last_end = end;
+            }
+        }
+        spans
+    }
+}
+
+/// Whether the span is not preceded or followed by an ASCII letter or digit
+fn is_delimited(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    !before.is_some_and(|c| c.is_ascii_alphanumeric()) && !after.is_some_and(|c| c.is_ascii_alphanumeric())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(recognizer: &Recognizer, text: &str) -> Vec<(String, String)> {
+        recognizer
+            .find(text)
+            .into_iter()
+            .map(|r| (r.text.to_string(), r.kind.as_str().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_builtin_patterns() {
+        let recognizer = Recognizer::builtin();
+        let cases = [
+            ("访问www.example.com。", vec![("www.example.com", "url")]),
+            ("链接(https://a.cn/x?y=1)", vec![("https://a.cn/x?y=1", "url")]),
+            (
+                "联系foo.bar@example.com或@李四",
+                vec![("foo.bar@example.com", "email"), ("@李四", "mention")],
+            ),
+            ("学C#和#rust", vec![("#rust", "hashtag")]),
+            (
+                "服务器192.168.0.1:8080和::1",
+                vec![("192.168.0.1:8080", "ip"), ("::1", "ip")],
+            ),
+            ("版本1.2.3-beta不是3.14", vec![("1.2.3-beta", "version")]),
+            ("日期2024.1.15发布v2024.1.15", vec![("v2024.1.15", "version")]),
+            ("注意:: 这里", vec![]),
+            ("a::b和fe80::1", vec![("fe80::1", "ip")]),
+            (
+                "哈哈 :) ^_^ T_T",
+                vec![(":)", "emoticon"), ("^_^", "emoticon"), ("T_T", "emoticon")],
+            ),
+            ("选项:D 正确", vec![]),
+            ("好的^_^", vec![("^_^", "emoticon")]),
+            ("10:30开会", vec![]),
+        ];
+        for (text, expected) in cases {
+            let expected: Vec<(String, String)> = expected
+                .into_iter()
+                .map(|(t, k)| (t.to_string(), k.to_string()))
+                .collect();
+            assert_eq!(find(&recognizer, text), expected, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_offsets_and_custom_patterns() {
+        let mut recognizer = Recognizer::new().with_builtin(PatternKind::Url);
+        recognizer.add_pattern("order", r"[A-Z]{2}\d{6}").unwrap();
+        assert!(recognizer.add_pattern("broken", "(").is_err());
+
+        let text = "订单AB123456见http://a.cn";
+        let spans = recognizer.find(text);
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].kind, PatternKind::Custom("order".into()));
+        assert_eq!((spans[0].start, spans[0].end), (2, 10));
+        assert_eq!((spans[0].byte_start, spans[0].byte_end), (6, 14));
+        assert_eq!(spans[1].text, "http://a.cn");
+        assert_eq!((spans[1].start, spans[1].end), (11, 22));
+        assert_eq!(&text[spans[1].byte_start..spans[1].byte_end], "http://a.cn");
+    }
+}
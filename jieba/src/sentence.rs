@@ -0,0 +1,249 @@
+use crate::{Token, char_count, is_cjk};
+
+/// Characters ending a sentence
+const TERMINATORS: &[char] = &['。', '｡', '！', '？', '!', '?', '…', '⋯', '；', ';'];
+
+/// Characters ending a clause, in addition to the sentence terminators
+const CLAUSE_SEPARATORS: &[char] = &['，', '、', '：', '︰', ',', ':'];
+
+/// Closing quotes and brackets kept with the terminator they follow
+const CLOSERS: &[char] = &[
+    '”', '’', '」', '』', '）', '】', '》', '〉', '〕', '〗', '］', '｝', ')', ']', '}',
+];
+
+/// Quotes both opening and closing a quotation, kept with the terminator they follow when
+/// they close a quote opened in the sentence
+const STRAIGHT_QUOTES: &[char] = &['"', '\''];
+
+/// A sentence or clause of a text, see [`SentenceSplitter`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Sentence<'a> {
+    /// Text of the sentence, without surrounding whitespace
+    pub text: &'a str,
+    /// Unicode start position of the sentence
+    pub start: usize,
+    /// Unicode end position of the sentence
+    pub end: usize,
+    /// Byte start position of the sentence
+    pub byte_start: usize,
+    /// Byte end position of the sentence
+    pub byte_end: usize,
+}
+
+impl<'a> Sentence<'a> {
+    /// The tokens of the sentence, out of `tokens` cut from the same text
+    ///
+    /// Tokens are assigned to the sentence they start in. Whitespace tokens between
+    /// sentences belong to no sentence.
+    pub fn tokens<'b, 't>(&self, tokens: &'b [Token<'t>]) -> &'b [Token<'t>] {
+        let first = tokens.partition_point(|token| token.byte_start < self.byte_start);
+        let last = tokens.partition_point(|token| token.byte_start < self.byte_end);
+        &tokens[first..last.max(first)]
+    }
+}
+
+/// Split text into sentences, or into clauses
+///
+/// Sentences end after `。！？!?…；;`, after an ASCII `.` followed by whitespace or
+/// Chinese text, and at line breaks. Runs of terminators such as `？！` or `……` and the
+/// closing quotes and brackets following them stay in the sentence, straight quotes `"'`
+/// only when they balance a quote opened in the sentence. In clause mode,
+/// sentences are also split after `，、：` and after ASCII `,` and `:` outside of numbers.
+///
+/// Spans have the same char and byte offsets as the [`Token`]s cut from the same text, see
+/// [`Sentence::tokens`].
+///
+/// # Examples
+///
+/// ```
+/// use jieba_rs::{Jieba, SentenceSplitter};
+///
+/// let jieba = Jieba::empty();
+/// let text = "他说：“走吧！”我们就走了。\n好";
+/// let sentences = SentenceSplitter::new().split(text);
+/// let texts: Vec<&str> = sentences.iter().map(|s| s.text).collect();
+/// assert_eq!(texts, vec!["他说：“走吧！”", "我们就走了。", "好"]);
+///
+/// let tokens = jieba.cut(text, false);
+/// let words: Vec<&str> = sentences[2].tokens(&tokens).iter().map(|t| t.word).collect();
+/// assert_eq!(words, vec!["好"]);
+///
+/// let clauses = SentenceSplitter::new().clauses(true).split("然而，价格是1,000元");
+/// assert_eq!(clauses[1].text, "价格是1,000元");
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SentenceSplitter {
+    clauses: bool,
+}
+
+impl SentenceSplitter {
+    /// Creates a sentence splitter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also split sentences into clauses
+    pub fn clauses(mut self, enabled: bool) -> Self {
+        self.clauses = enabled;
+        self
+    }
+
+    /// Split `text` into sentences, or clauses, without the whitespace between them
+    pub fn split<'a>(&self, text: &'a str) -> Vec<Sentence<'a>> {
+        let chars: Vec<(usize, char)> = text.char_indices().collect();
+        let byte_at = |i: usize| chars.get(i).map_or(text.len(), |&(byte, _)| byte);
+        let mut spans = Spans {
+            text,
+            sentences: Vec::new(),
+            byte: 0,
+            char: 0,
+        };
+        let mut start = 0;
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i].1;
+            if is_line_break(c) {
+                spans.push(start, byte_at(i));
+                start = byte_at(i + 1);
+                i += 1;
+            } else if self.ends_span(&chars, i) {
+                let mut j = i + 1;
+                while j < chars.len() && self.ends_span(&chars, j) {
+                    j += 1;
+                }
+                while j < chars.len() && closes(&text[start..byte_at(j)], chars[j].1) {
+                    j += 1;
+                }
+                spans.push(start, byte_at(j));
+                start = byte_at(j);
+                i = j;
+            } else {
+                i += 1;
+            }
+        }
+        spans.push(start, text.len());
+        spans.sentences
+    }
+
+    /// Whether the char at `i` ends a sentence or clause
+    fn ends_span(&self, chars: &[(usize, char)], i: usize) -> bool {
+        let c = chars[i].1;
+        let next = chars.get(i + 1).map(|&(_, c)| c);
+        match c {
+            '.' => {
+                let after = chars[i..].iter().map(|&(_, c)| c).find(|&c| c != '.');
+                after.is_none_or(|c| {
+                    c.is_whitespace() || CLOSERS.contains(&c) || STRAIGHT_QUOTES.contains(&c) || is_cjk(c)
+                })
+            }
+            ',' | ':' if self.clauses => {
+                let between_digits =
+                    i > 0 && chars[i - 1].1.is_ascii_digit() && next.is_some_and(|c| c.is_ascii_digit());
+                !between_digits
+            }
+            _ => TERMINATORS.contains(&c) || (self.clauses && CLAUSE_SEPARATORS.contains(&c)),
+        }
+    }
+}
+
+/// Whether `c` closes a quote or bracket of `sentence`, the sentence before it
+fn closes(sentence: &str, c: char) -> bool {
+    CLOSERS.contains(&c) || (STRAIGHT_QUOTES.contains(&c) && sentence.matches(c).count() % 2 == 1)
+}
+
+fn is_line_break(c: char) -> bool {
+    matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}
+
+/// Sentences with incrementally tracked char offsets
+struct Spans<'a> {
+    text: &'a str,
+    sentences: Vec<Sentence<'a>>,
+    byte: usize,
+    char: usize,
+}
+
+impl<'a> Spans<'a> {
+    /// Push the span between `byte_start` and `byte_end` without surrounding whitespace,
+    /// unless it is empty
+    fn push(&mut self, byte_start: usize, byte_end: usize) {
+        let span = &self.text[byte_start..byte_end];
+        let trimmed = span.trim_start();
+        let byte_start = byte_start + (span.len() - trimmed.len());
+        let byte_end = byte_start + trimmed.trim_end().len();
+        if byte_start == byte_end {
+            return;
+        }
+        let start = self.char + char_count(&self.text[self.byte..byte_start]);
+        let end = start + char_count(&self.text[byte_start..byte_end]);
+        self.byte = byte_end;
+        self.char = end;
+        self.sentences.push(Sentence {
+            text: &self.text[byte_start..byte_end],
+            start,
+            end,
+            byte_start,
+            byte_end,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts<'a>(sentences: &[Sentence<'a>]) -> Vec<&'a str> {
+        sentences.iter().map(|s| s.text).collect()
+    }
+
+    #[test]
+    fn test_split_sentences() {
+        let splitter = SentenceSplitter::new();
+        let cases = [
+            (
+                "你好。你是谁？嗯……不知道！！",
+                vec!["你好。", "你是谁？", "嗯……", "不知道！！"],
+            ),
+            ("他说：“好的。”然后走了", vec!["他说：“好的。”", "然后走了"]),
+            ("Pi is 3.14. Really... Yes!", vec!["Pi is 3.14.", "Really...", "Yes!"]),
+            (
+                "版本v1.2发布了.下一步；再说",
+                vec!["版本v1.2发布了.", "下一步；", "再说"],
+            ),
+            ("第一行\r\n\r\n  第二行  \n", vec!["第一行", "第二行"]),
+            ("（完。）后记", vec!["（完。）", "后记"]),
+            ("他说完了。\"好的\"她答。", vec!["他说完了。", "\"好的\"她答。"]),
+            ("他说\"好的。\"然后走了", vec!["他说\"好的。\"", "然后走了"]),
+            ("He said 'no.' Then 'yes.'", vec!["He said 'no.'", "Then 'yes.'"]),
+            ("", vec![]),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(texts(&splitter.split(text)), expected, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_split_clauses() {
+        let splitter = SentenceSplitter::new().clauses(true);
+        let sentences = splitter.split("首先，时间是10:30、地点：北京, ok。");
+        assert_eq!(
+            texts(&sentences),
+            vec!["首先，", "时间是10:30、", "地点：", "北京,", "ok。"]
+        );
+    }
+
+    #[test]
+    fn test_offsets() {
+        let text = " 甲。 乙！";
+        let sentences = SentenceSplitter::new().split(text);
+        assert_eq!((sentences[0].start, sentences[0].end), (1, 3));
+        assert_eq!((sentences[1].start, sentences[1].end), (4, 6));
+        for sentence in &sentences {
+            assert_eq!(&text[sentence.byte_start..sentence.byte_end], sentence.text);
+        }
+
+        let tokens = crate::Jieba::empty().cut(text, false);
+        let words: Vec<&str> = sentences[1].tokens(&tokens).iter().map(|t| t.word).collect();
+        assert_eq!(words, vec!["乙", "！"]);
+    }
+}
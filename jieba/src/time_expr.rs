@@ -0,0 +1,765 @@
+use std::fmt;
+
+use crate::quantity::{integer_len, is_digit_by_digit, parse_number};
+use crate::{PosCategory, PosTag, Tag};
+
+/// Unit of time, the precision of a point in time or the unit of a duration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TimeUnit {
+    /// Calendar year
+    Year,
+    /// Calendar month
+    Month,
+    /// Week, from Monday to Sunday
+    Week,
+    /// Day
+    Day,
+    /// Hour
+    Hour,
+    /// Minute
+    Minute,
+    /// Second
+    Second,
+}
+
+/// A calendar date and time of day, without time zone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    /// Year of the proleptic Gregorian calendar
+    pub year: i32,
+    /// Month, from 1 to 12
+    pub month: u32,
+    /// Day of the month, from 1
+    pub day: u32,
+    /// Hour, from 0 to 23
+    pub hour: u32,
+    /// Minute, from 0 to 59
+    pub minute: u32,
+    /// Second, from 0 to 59
+    pub second: u32,
+}
+
+impl DateTime {
+    /// Creates a datetime, `None` if a field is out of range
+    pub fn new(year: i32, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> Option<DateTime> {
+        let valid = (1..=12).contains(&month)
+            && (1..=days_in_month(year, month)).contains(&day)
+            && hour < 24
+            && minute < 60
+            && second < 60;
+        valid.then_some(DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    /// Day of the week, from 1 for Monday to 7 for Sunday
+    pub fn weekday(&self) -> u32 {
+        // 1970-01-01 is a Thursday
+        (self.days() + 3).rem_euclid(7) as u32 + 1
+    }
+
+    /// Days since 1970-01-01
+    fn days(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day)
+    }
+
+    fn set_days(&mut self, days: i64) {
+        (self.year, self.month, self.day) = civil_from_days(days);
+    }
+
+    fn set_year(&mut self, year: i32) {
+        self.year = year;
+        self.day = self.day.min(days_in_month(self.year, self.month));
+    }
+
+    fn set_month(&mut self, month: u32) {
+        self.month = month;
+        self.day = self.day.min(days_in_month(self.year, self.month));
+    }
+
+    /// Adds months, keeping the day within the resulting month
+    fn add_months(&mut self, n: i64) {
+        let months = self.year as i64 * 12 + self.month as i64 - 1 + n;
+        self.year = months.div_euclid(12) as i32;
+        self.set_month(months.rem_euclid(12) as u32 + 1);
+    }
+
+    fn add_seconds(&mut self, n: i64) {
+        let seconds = self.days() * 86400 + (self.hour * 3600 + self.minute * 60 + self.second) as i64 + n;
+        self.set_days(seconds.div_euclid(86400));
+        let rem = seconds.rem_euclid(86400) as u32;
+        (self.hour, self.minute, self.second) = (rem / 3600, rem / 60 % 60, rem % 60);
+    }
+
+    fn add(&mut self, unit: TimeUnit, n: i64) {
+        match unit {
+            TimeUnit::Year => self.add_months(12 * n),
+            TimeUnit::Month => self.add_months(n),
+            TimeUnit::Week => self.set_days(self.days() + 7 * n),
+            TimeUnit::Day => self.set_days(self.days() + n),
+            TimeUnit::Hour => self.add_seconds(3600 * n),
+            TimeUnit::Minute => self.add_seconds(60 * n),
+            TimeUnit::Second => self.add_seconds(n),
+        }
+    }
+
+    /// Moves to the given day of the same week, from 1 for Monday to 7 for Sunday
+    fn set_weekday(&mut self, weekday: u32) {
+        self.set_days(self.days() + weekday as i64 - self.weekday() as i64);
+    }
+
+    /// Resets the fields finer than `unit` to their start
+    fn truncate(&mut self, unit: TimeUnit) {
+        if unit < TimeUnit::Second {
+            self.second = 0;
+        }
+        if unit < TimeUnit::Minute {
+            self.minute = 0;
+        }
+        if unit < TimeUnit::Hour {
+            self.hour = 0;
+        }
+        if unit == TimeUnit::Week {
+            self.set_weekday(1);
+        }
+        if unit < TimeUnit::Week {
+            self.day = 1;
+        }
+        if unit < TimeUnit::Month {
+            self.month = 1;
+        }
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date
+fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let year = year as i64 - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Proleptic Gregorian date of a number of days since 1970-01-01
+fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year as i32, month as u32, day as u32)
+}
+
+/// Normalized value of a time expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeValue {
+    /// A point in time resolved against the reference datetime, such as 明天下午三点.
+    ///
+    /// Fields finer than `granularity` are reset to their start, except for points
+    /// shifted from the reference by a duration, such as 三小时后.
+    Point { datetime: DateTime, granularity: TimeUnit },
+    /// A duration that is not anchored to the reference datetime, such as 三天
+    Duration { amount: i64, unit: TimeUnit },
+}
+
+/// A time expression span
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TimeExpression<'a> {
+    /// Text of the time expression
+    pub text: &'a str,
+    /// Normalized value of the time expression
+    pub value: TimeValue,
+    /// Unicode start position of the time expression in the original input
+    pub start: usize,
+    /// Unicode end position of the time expression in the original input
+    pub end: usize,
+    /// Byte start position of the time expression in the original input
+    pub byte_start: usize,
+    /// Byte end position of the time expression in the original input
+    pub byte_end: usize,
+}
+
+/// Words shifting the reference by whole calendar units
+const RELATIVE_WORDS: &[(&str, TimeUnit, i64)] = &[
+    ("大前年", TimeUnit::Year, -3),
+    ("前年", TimeUnit::Year, -2),
+    ("去年", TimeUnit::Year, -1),
+    ("今年", TimeUnit::Year, 0),
+    ("明年", TimeUnit::Year, 1),
+    ("来年", TimeUnit::Year, 1),
+    ("后年", TimeUnit::Year, 2),
+    ("大后年", TimeUnit::Year, 3),
+    ("上个月", TimeUnit::Month, -1),
+    ("上月", TimeUnit::Month, -1),
+    ("这个月", TimeUnit::Month, 0),
+    ("本月", TimeUnit::Month, 0),
+    ("下个月", TimeUnit::Month, 1),
+    ("下月", TimeUnit::Month, 1),
+    ("上个星期", TimeUnit::Week, -1),
+    ("上星期", TimeUnit::Week, -1),
+    ("上礼拜", TimeUnit::Week, -1),
+    ("上周", TimeUnit::Week, -1),
+    ("这个星期", TimeUnit::Week, 0),
+    ("这周", TimeUnit::Week, 0),
+    ("本周", TimeUnit::Week, 0),
+    ("下个星期", TimeUnit::Week, 1),
+    ("下星期", TimeUnit::Week, 1),
+    ("下礼拜", TimeUnit::Week, 1),
+    ("下周", TimeUnit::Week, 1),
+    ("大前天", TimeUnit::Day, -3),
+    ("前天", TimeUnit::Day, -2),
+    ("昨天", TimeUnit::Day, -1),
+    ("昨日", TimeUnit::Day, -1),
+    ("今天", TimeUnit::Day, 0),
+    ("今日", TimeUnit::Day, 0),
+    ("明天", TimeUnit::Day, 1),
+    ("明日", TimeUnit::Day, 1),
+    ("后天", TimeUnit::Day, 2),
+    ("大后天", TimeUnit::Day, 3),
+];
+
+/// Words for a day and part of it, with the day offset and the part of day
+const DAY_PERIOD_WORDS: &[(&str, i64, &str)] = &[
+    ("昨晚", -1, "晚上"),
+    ("今早", 0, "早上"),
+    ("今晚", 0, "晚上"),
+    ("明早", 1, "早上"),
+    ("明晚", 1, "晚上"),
+];
+
+/// Parts of the day, with their default hour and the hours before which a clock time
+/// is in the afternoon, such as 3 in 下午三点, or midnight of the next day for 12 in
+/// 晚上12点
+const PERIODS: &[(&str, u32, u32)] = &[
+    ("凌晨", 3, 0),
+    ("早上", 8, 0),
+    ("早晨", 8, 0),
+    ("上午", 9, 0),
+    ("中午", 12, 6),
+    ("下午", 15, 12),
+    ("傍晚", 18, 12),
+    ("晚上", 20, 13),
+    ("夜里", 22, 13),
+    ("半夜", 0, 0),
+];
+
+/// Units of a duration, longest first, and whether a number with the unit alone is a duration
+const DURATION_UNITS: &[(&str, TimeUnit, bool)] = &[
+    ("个星期", TimeUnit::Week, true),
+    ("个小时", TimeUnit::Hour, true),
+    ("个月", TimeUnit::Month, true),
+    ("星期", TimeUnit::Week, true),
+    ("礼拜", TimeUnit::Week, true),
+    ("小时", TimeUnit::Hour, true),
+    ("钟头", TimeUnit::Hour, true),
+    ("分钟", TimeUnit::Minute, true),
+    ("秒钟", TimeUnit::Second, true),
+    ("年", TimeUnit::Year, false),
+    ("月", TimeUnit::Month, false),
+    ("周", TimeUnit::Week, true),
+    ("天", TimeUnit::Day, true),
+    ("日", TimeUnit::Day, false),
+    ("分", TimeUnit::Minute, false),
+    ("秒", TimeUnit::Second, false),
+];
+
+const DIRECTIONS: &[(&str, i64)] = &[
+    ("以后", 1),
+    ("之后", 1),
+    ("后", 1),
+    ("以前", -1),
+    ("之前", -1),
+    ("前", -1),
+];
+
+fn strip_word<'t, T: Copy>(text: &'t str, words: &[(&str, T)]) -> Option<(&'t str, T)> {
+    words
+        .iter()
+        .find_map(|&(word, value)| text.strip_prefix(word).map(|rest| (rest, value)))
+}
+
+fn strip_any<'t>(text: &'t str, words: &[&str]) -> Option<&'t str> {
+    words.iter().find_map(|word| text.strip_prefix(word))
+}
+
+/// Integer at the start of `text`, with its text and the rest of `text`
+fn strip_integer(text: &str) -> Option<(&str, i64, &str)> {
+    let len = integer_len(text);
+    let value = parse_number(&text[..len])?;
+    // Anything larger is not a date, time or duration and would overflow the arithmetic
+    if value > 1e6 {
+        return None;
+    }
+    Some((&text[..len], value as i64, &text[len..]))
+}
+
+/// Day of the week at the start of `text`, such as `五` in `周五`
+fn strip_weekday(text: &str) -> Option<(&str, u32)> {
+    let mut chars = text.chars();
+    let c = chars.next()?;
+    let weekday = match c {
+        '日' | '天' | '七' => 7,
+        '1'..='7' => c.to_digit(10)?,
+        // Saturday, for 周末
+        '末' => 6,
+        _ => "一二三四五六".chars().position(|w| w == c)? as u32 + 1,
+    };
+    Some((chars.as_str(), weekday))
+}
+
+/// Duration at the start of `text`, with its direction if followed by 后 or 前
+fn strip_duration(text: &str) -> Option<(&str, i64, TimeUnit, Option<i64>, bool)> {
+    let (number, amount, rest) = strip_integer(text)?;
+    let (rest, (unit, bare)) = DURATION_UNITS
+        .iter()
+        .find_map(|&(word, unit, bare)| rest.strip_prefix(word).map(|rest| (rest, (unit, bare))))?;
+    // 三年 is a duration, 2013年 a year
+    let bare = bare || (unit == TimeUnit::Year && !is_digit_by_digit(number));
+    match strip_word(rest, DIRECTIONS) {
+        Some((rest, direction)) => Some((rest, amount, unit, Some(direction), bare)),
+        None => Some((rest, amount, unit, None, bare)),
+    }
+}
+
+/// Resolves the parts of a time expression one after the other
+struct Resolver {
+    reference: DateTime,
+    datetime: DateTime,
+    granularity: Option<TimeUnit>,
+    /// Whether fields finer than the granularity are reset
+    truncate: bool,
+    /// Hours before which a clock time is in the afternoon
+    pm_before: u32,
+}
+
+impl Resolver {
+    fn refine(&mut self, unit: TimeUnit, truncate: bool) {
+        self.granularity = Some(self.granularity.map_or(unit, |granularity| granularity.max(unit)));
+        self.truncate |= truncate;
+    }
+
+    /// Resolves the part at the start of `text`, returning the rest of `text`
+    fn part<'t>(&mut self, text: &'t str) -> Option<&'t str> {
+        if let Some((rest, amount, unit, Some(direction), _)) = strip_duration(text) {
+            self.datetime.add(unit, amount * direction);
+            self.refine(unit, false);
+            return Some(rest);
+        }
+        if let Some((word, unit, n)) = RELATIVE_WORDS.iter().find(|(word, ..)| text.starts_with(word)) {
+            let rest = &text[word.len()..];
+            self.datetime.add(*unit, *n);
+            if *unit == TimeUnit::Week
+                && let Some((rest, weekday)) = strip_weekday(rest)
+            {
+                self.datetime.set_weekday(weekday);
+                self.refine(TimeUnit::Day, true);
+                return Some(rest);
+            }
+            self.refine(*unit, true);
+            return Some(rest);
+        }
+        if let Some((rest, (days, period))) = DAY_PERIOD_WORDS
+            .iter()
+            .find_map(|&(word, days, period)| text.strip_prefix(word).map(|rest| (rest, (days, period))))
+        {
+            self.datetime.add(TimeUnit::Day, days);
+            self.refine(TimeUnit::Day, true);
+            self.period(period);
+            return Some(rest);
+        }
+        if let Some(rest) = strip_any(text, &["周", "星期", "礼拜"])
+            && let Some((rest, weekday)) = strip_weekday(rest)
+        {
+            self.datetime.set_weekday(weekday);
+            self.refine(TimeUnit::Day, true);
+            return Some(rest);
+        }
+        if let Some(&(word, ..)) = PERIODS.iter().find(|(word, ..)| text.starts_with(word)) {
+            self.period(word);
+            return Some(&text[word.len()..]);
+        }
+        self.numbered(text)
+    }
+
+    fn period(&mut self, period: &str) {
+        if let Some(&(_, hour, pm_before)) = PERIODS.iter().find(|(word, ..)| *word == period) {
+            self.datetime.hour = hour;
+            self.pm_before = pm_before;
+            self.refine(TimeUnit::Hour, true);
+        }
+    }
+
+    /// Year, month, day or clock time starting with a number
+    fn numbered<'t>(&mut self, text: &'t str) -> Option<&'t str> {
+        let (number, n, rest) = strip_integer(text)?;
+        if let Some(rest) = rest.strip_prefix('年')
+            && number.chars().count() >= 2
+            && is_digit_by_digit(number)
+        {
+            let mut year = n as i32;
+            if year < 100 {
+                // 90年 is 1990 and 13年 is 2013
+                let reference = self.reference.year;
+                year += reference - reference.rem_euclid(100);
+                if year > reference + 20 {
+                    year -= 100;
+                }
+            }
+            self.datetime.set_year(year);
+            self.refine(TimeUnit::Year, true);
+            return Some(rest);
+        }
+        if let Some(rest) = rest.strip_prefix('月')
+            && (1..=12).contains(&n)
+        {
+            self.datetime.set_month(n as u32);
+            self.refine(TimeUnit::Month, true);
+            return Some(rest);
+        }
+        if let Some(rest) = strip_any(rest, &["日", "号"])
+            && (1..=days_in_month(self.datetime.year, self.datetime.month) as i64).contains(&n)
+        {
+            self.datetime.day = n as u32;
+            self.refine(TimeUnit::Day, true);
+            return Some(rest);
+        }
+        self.clock(n, rest)
+    }
+
+    /// Clock time such as 三点半, 15点20分 or 15:20, with the hour already read
+    fn clock<'t>(&mut self, hour: i64, text: &'t str) -> Option<&'t str> {
+        if !(0..=24).contains(&hour) {
+            return None;
+        }
+        let mut minute = None;
+        let mut second = None;
+        let rest = if let Some(rest) = strip_any(text, &[":", "："]) {
+            let (_, m, mut rest) = strip_integer(rest)?;
+            minute = Some(m);
+            if let Some(after) = strip_any(rest, &[":", "："])
+                && let Some((_, s, after)) = strip_integer(after)
+            {
+                second = Some(s);
+                rest = after;
+            }
+            rest
+        } else {
+            let mut rest = strip_any(text, &["点钟", "点", "时"])?;
+            if let Some((after, m)) = strip_word(rest, &[("半", 30), ("一刻", 15), ("三刻", 45), ("整", 0)]) {
+                minute = Some(m);
+                rest = after;
+            } else if let Some((_, m, after)) = strip_integer(rest)
+                && let Some(after) = strip_any(after, &["分钟", "分"])
+            {
+                minute = Some(m);
+                rest = after;
+                if let Some((_, s, after)) = strip_integer(rest)
+                    && let Some(after) = strip_any(after, &["秒钟", "秒"])
+                {
+                    second = Some(s);
+                    rest = after;
+                }
+            }
+            rest
+        };
+        if minute.is_some_and(|m| !(0..60).contains(&m)) || second.is_some_and(|s| !(0..60).contains(&s)) {
+            return None;
+        }
+
+        let mut hour = hour as u32;
+        if hour < self.pm_before {
+            hour += 12;
+        }
+        if hour == 24 {
+            self.datetime.add(TimeUnit::Day, 1);
+            hour = 0;
+        }
+        self.datetime.hour = hour;
+        self.datetime.minute = minute.unwrap_or(0) as u32;
+        self.datetime.second = second.unwrap_or(0) as u32;
+        let unit = match (minute, second) {
+            (_, Some(_)) => TimeUnit::Second,
+            (Some(_), None) => TimeUnit::Minute,
+            (None, None) => TimeUnit::Hour,
+        };
+        self.refine(unit, true);
+        Some(rest)
+    }
+}
+
+/// Parses the time expression at the start of `text`, returning its byte length and value
+fn parse(text: &str, reference: DateTime) -> Option<(usize, TimeValue)> {
+    if let Some((rest, amount, unit, None, true)) = strip_duration(text) {
+        return Some((text.len() - rest.len(), TimeValue::Duration { amount, unit }));
+    }
+
+    let mut resolver = Resolver {
+        reference,
+        datetime: reference,
+        granularity: None,
+        truncate: false,
+        pm_before: 0,
+    };
+    let mut rest = text;
+    while let Some(after) = resolver.part(rest) {
+        rest = after;
+    }
+    let granularity = resolver.granularity?;
+    let mut datetime = resolver.datetime;
+    if resolver.truncate {
+        datetime.truncate(granularity);
+    }
+    Some((text.len() - rest.len(), TimeValue::Point { datetime, granularity }))
+}
+
+/// Parses the time expression starting at the first of `tags`, ending at a token boundary
+fn parse_tokens(sentence: &str, tags: &[Tag<'_>], reference: DateTime) -> Option<(usize, TimeValue)> {
+    let byte_start = tags.first()?.byte_start;
+    let mut limit = tags.last()?.byte_end;
+    loop {
+        let (len, value) = parse(&sentence[byte_start..limit], reference)?;
+        let end = byte_start + len;
+        // A shorter expression may have a different value, such as 3天 in 3天后面
+        let boundary = tags.iter().map(|t| t.byte_end).take_while(|&e| e <= end).last()?;
+        if boundary == end {
+            return Some((len, value));
+        }
+        limit = boundary;
+    }
+}
+
+impl<'a> TimeExpression<'a> {
+    /// Extracts the time expressions of `sentence` from its tags.
+    ///
+    /// `tags` are the output of [`Jieba::tag`](crate::Jieba::tag) for `sentence`. Starting
+    /// from a `t`, `m` or `q` token, or a token that is a time expression by itself, such as
+    /// 上周五, adjacent whole tokens are merged into one time expression. Dates and times are
+    /// resolved against `reference`, the datetime the text is relative to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jieba_rs::{DateTime, Jieba, TimeExpression, TimeUnit, TimeValue};
+    ///
+    /// let mut jieba = Jieba::empty();
+    /// jieba.add_word("明天", Some(100), Some("t"));
+    /// jieba.add_word("下午", Some(100), Some("t"));
+    /// jieba.add_word("三点", Some(100), Some("m"));
+    ///
+    /// let sentence = "明天下午三点开会";
+    /// let tags = jieba.tag(sentence, false);
+    /// let reference = DateTime::new(2013, 5, 1, 10, 0, 0).unwrap();
+    /// let times = TimeExpression::from_tags(sentence, &tags, reference);
+    /// assert_eq!(times[0].text, "明天下午三点");
+    /// assert_eq!(
+    ///     times[0].value,
+    ///     TimeValue::Point {
+    ///         datetime: DateTime::new(2013, 5, 2, 15, 0, 0).unwrap(),
+    ///         granularity: TimeUnit::Hour,
+    ///     }
+    /// );
+    /// ```
+    pub fn from_tags(sentence: &'a str, tags: &[Tag<'a>], reference: DateTime) -> Vec<TimeExpression<'a>> {
+        let mut expressions = Vec::new();
+        let mut i = 0;
+        while i < tags.len() {
+            let tag = &tags[i];
+            let pos = tag.pos();
+            let candidate = matches!(pos, PosTag::T | PosTag::Tg)
+                || matches!(pos.category(), PosCategory::Numeral | PosCategory::Classifier)
+                || parse(tag.word, reference).is_some_and(|(len, _)| len == tag.word.len());
+            let Some((len, value)) = candidate
+                .then(|| parse_tokens(sentence, &tags[i..], reference))
+                .flatten()
+            else {
+                i += 1;
+                continue;
+            };
+            let text = &sentence[tag.byte_start..tag.byte_start + len];
+            let expression = TimeExpression {
+                text,
+                value,
+                start: tag.start,
+                end: tag.start + text.chars().count(),
+                byte_start: tag.byte_start,
+                byte_end: tag.byte_start + len,
+            };
+            while i < tags.len() && tags[i].byte_start < expression.byte_end {
+                i += 1;
+            }
+            expressions.push(expression);
+        }
+        expressions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sunday
+    fn reference() -> DateTime {
+        DateTime::new(2026, 10, 18, 10, 25, 0).unwrap()
+    }
+
+    fn point(text: &str) -> Option<(String, String, TimeUnit)> {
+        match parse(text, reference())? {
+            (len, TimeValue::Point { datetime, granularity }) => {
+                Some((text[..len].to_string(), datetime.to_string(), granularity))
+            }
+            (_, TimeValue::Duration { .. }) => None,
+        }
+    }
+
+    #[test]
+    fn test_datetime() {
+        assert_eq!(DateTime::new(2013, 5, 1, 0, 0, 0).unwrap().weekday(), 3);
+        assert_eq!(reference().weekday(), 7);
+        assert_eq!(DateTime::new(2013, 2, 29, 0, 0, 0), None);
+
+        let mut datetime = DateTime::new(2024, 1, 31, 23, 59, 30).unwrap();
+        datetime.add(TimeUnit::Month, 1);
+        assert_eq!(datetime.to_string(), "2024-02-29 23:59:30");
+        datetime.add(TimeUnit::Second, 30);
+        assert_eq!(datetime.to_string(), "2024-03-01 00:00:00");
+        datetime.add(TimeUnit::Day, -366);
+        assert_eq!(datetime.to_string(), "2023-03-01 00:00:00");
+    }
+
+    #[test]
+    fn test_parse() {
+        let cases = [
+            (
+                "明天下午三点开会",
+                "明天下午三点",
+                "2026-10-19 15:00:00",
+                TimeUnit::Hour,
+            ),
+            ("2013年5月", "2013年5月", "2013-05-01 00:00:00", TimeUnit::Month),
+            (
+                "二〇一三年五月一日",
+                "二〇一三年五月一日",
+                "2013-05-01 00:00:00",
+                TimeUnit::Day,
+            ),
+            ("上周五", "上周五", "2026-10-09 00:00:00", TimeUnit::Day),
+            ("下周", "下周", "2026-10-19 00:00:00", TimeUnit::Week),
+            ("周三", "周三", "2026-10-14 00:00:00", TimeUnit::Day),
+            ("三天后", "三天后", "2026-10-21 10:25:00", TimeUnit::Day),
+            ("两个小时以后", "两个小时以后", "2026-10-18 12:25:00", TimeUnit::Hour),
+            ("三年前的今天", "三年前", "2023-10-18 10:25:00", TimeUnit::Year),
+            ("上个月", "上个月", "2026-09-01 00:00:00", TimeUnit::Month),
+            ("90年", "90年", "1990-01-01 00:00:00", TimeUnit::Year),
+            ("今晚八点半", "今晚八点半", "2026-10-18 20:30:00", TimeUnit::Minute),
+            ("中午12点", "中午12点", "2026-10-18 12:00:00", TimeUnit::Hour),
+            ("晚上12点", "晚上12点", "2026-10-19 00:00:00", TimeUnit::Hour),
+            ("夜里12点半", "夜里12点半", "2026-10-19 00:30:00", TimeUnit::Minute),
+            ("晚上十一点", "晚上十一点", "2026-10-18 23:00:00", TimeUnit::Hour),
+            ("下午3:30", "下午3:30", "2026-10-18 15:30:00", TimeUnit::Minute),
+            (
+                "十二点十分五秒",
+                "十二点十分五秒",
+                "2026-10-18 12:10:05",
+                TimeUnit::Second,
+            ),
+        ];
+        for (text, expected, datetime, granularity) in cases {
+            assert_eq!(
+                point(text),
+                Some((expected.to_string(), datetime.to_string(), granularity)),
+                "{text}"
+            );
+        }
+
+        assert_eq!(
+            parse("三天", reference()),
+            Some((
+                "三天".len(),
+                TimeValue::Duration {
+                    amount: 3,
+                    unit: TimeUnit::Day
+                }
+            ))
+        );
+        assert_eq!(parse("十分重要", reference()), None);
+        assert_eq!(parse("三个苹果", reference()), None);
+    }
+
+    #[test]
+    fn test_from_tags() {
+        let sentence = "我们上周五和3天后见面";
+        let words = [
+            ("我们", "r"),
+            ("上周", "t"),
+            ("五", "m"),
+            ("和", "c"),
+            ("3", "m"),
+            ("天", "q"),
+            ("后", "f"),
+            ("见面", "v"),
+        ];
+        let tags = Tag::from_pairs(words);
+        assert_eq!(tags.iter().map(|t| t.word).collect::<String>(), sentence);
+        let expressions = TimeExpression::from_tags(sentence, &tags, reference());
+        let spans: Vec<_> = expressions.iter().map(|e| (e.text, e.start, e.end)).collect();
+        assert_eq!(spans, vec![("上周五", 2, 5), ("3天后", 6, 9)]);
+        assert_eq!(
+            expressions[1].value,
+            TimeValue::Point {
+                datetime: DateTime::new(2026, 10, 21, 10, 25, 0).unwrap(),
+                granularity: TimeUnit::Day,
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_tags_token_boundaries() {
+        let sentence = "3天后面";
+        let tags = Tag::from_pairs([("3", "m"), ("天", "q"), ("后面", "f")]);
+        assert_eq!(tags.iter().map(|t| t.word).collect::<String>(), sentence);
+        let expressions = TimeExpression::from_tags(sentence, &tags, reference());
+        assert_eq!(expressions.len(), 1);
+        assert_eq!(expressions[0].text, "3天");
+        assert_eq!(
+            expressions[0].value,
+            TimeValue::Duration {
+                amount: 3,
+                unit: TimeUnit::Day
+            }
+        );
+    }
+}
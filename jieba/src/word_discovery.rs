@@ -0,0 +1,444 @@
+use std::cmp::Ordering;
+use std::io::{self, Write};
+
+use crate::FxHashMap;
+use crate::{Jieba, SplitByCharacterClass, is_cjk};
+
+/// Creates a WordDiscoveryConfig state that contains the thresholds used to
+/// select candidate words.
+///
+/// Use [`WordDiscoveryConfigBuilder`] to change the defaults.
+///
+/// # Examples
+///
+/// ```
+/// use jieba_rs::WordDiscoveryConfig;
+///
+/// let config = WordDiscoveryConfig::default();
+/// assert_eq!(4, config.max_word_len());
+/// assert_eq!(5, config.min_count());
+///
+/// let changed = WordDiscoveryConfig::builder().max_word_len(6).min_count(10).build();
+/// assert_eq!(6, changed.max_word_len());
+/// assert_eq!(10, changed.min_count());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordDiscoveryConfig {
+    max_word_len: usize,
+    min_count: u64,
+    min_pmi: f64,
+    min_entropy: f64,
+}
+
+impl Default for WordDiscoveryConfig {
+    fn default() -> WordDiscoveryConfig {
+        WordDiscoveryConfig::builder().build()
+    }
+}
+
+impl WordDiscoveryConfig {
+    /// Creates a new [`WordDiscoveryConfigBuilder`] with default values.
+    pub fn builder() -> WordDiscoveryConfigBuilder {
+        WordDiscoveryConfigBuilder::default()
+    }
+
+    /// Gets the maximum number of Unicode Scalar Values of a candidate word.
+    pub fn max_word_len(&self) -> usize {
+        self.max_word_len
+    }
+
+    /// Gets the minimum number of occurrences of a candidate word.
+    pub fn min_count(&self) -> u64 {
+        self.min_count
+    }
+
+    /// Gets the minimum pointwise mutual information between the parts of a candidate word.
+    pub fn min_pmi(&self) -> f64 {
+        self.min_pmi
+    }
+
+    /// Gets the minimum left and right neighbour entropy of a candidate word.
+    pub fn min_entropy(&self) -> f64 {
+        self.min_entropy
+    }
+}
+
+/// Builds a [`WordDiscoveryConfig`], starting from the defaults: candidates of up to 4
+/// Unicode Scalar Values, seen at least 5 times, with a PMI of at least 3.0 and a neighbour
+/// entropy of at least 1.0.
+#[derive(Debug, Clone)]
+pub struct WordDiscoveryConfigBuilder {
+    max_word_len: usize,
+    min_count: u64,
+    min_pmi: f64,
+    min_entropy: f64,
+}
+
+impl Default for WordDiscoveryConfigBuilder {
+    fn default() -> Self {
+        WordDiscoveryConfigBuilder {
+            max_word_len: 4,
+            min_count: 5,
+            min_pmi: 3.0,
+            min_entropy: 1.0,
+        }
+    }
+}
+
+impl WordDiscoveryConfigBuilder {
+    /// Builds the [`WordDiscoveryConfig`] with the current configuration.
+    pub fn build(self) -> WordDiscoveryConfig {
+        WordDiscoveryConfig {
+            max_word_len: self.max_word_len,
+            min_count: self.min_count,
+            min_pmi: self.min_pmi,
+            min_entropy: self.min_entropy,
+        }
+    }
+
+    /// Sets the maximum number of Unicode Scalar Values of a candidate word, at least 2.
+    pub fn max_word_len(mut self, len: usize) -> Self {
+        self.max_word_len = len.max(2);
+        self
+    }
+
+    /// Sets the number of occurrences that any n-gram less than it will not be considered.
+    pub fn min_count(mut self, count: u64) -> Self {
+        self.min_count = count;
+        self
+    }
+
+    /// Sets the minimum pointwise mutual information (natural log) over all the ways of
+    /// splitting a candidate into two parts.
+    pub fn min_pmi(mut self, pmi: f64) -> Self {
+        self.min_pmi = pmi;
+        self
+    }
+
+    /// Sets the minimum entropy (natural log) of both the left and the right neighbours
+    /// of a candidate.
+    pub fn min_entropy(mut self, entropy: f64) -> Self {
+        self.min_entropy = entropy;
+        self
+    }
+}
+
+/// A candidate word found by [`WordDiscovery`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordCandidate {
+    /// The candidate word
+    pub word: String,
+    /// Number of occurrences in the corpus
+    pub count: u64,
+    /// Suggested dictionary frequency, large enough for the word to be kept together
+    pub freq: usize,
+    /// Minimum pointwise mutual information between two parts of the word
+    pub pmi: f64,
+    /// Entropy of the characters preceding the word
+    pub left_entropy: f64,
+    /// Entropy of the characters following the word
+    pub right_entropy: f64,
+    /// Ranking score, `pmi + min(left_entropy, right_entropy)`
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct NgramStats {
+    count: u64,
+    left: FxHashMap<char, u64>,
+    right: FxHashMap<char, u64>,
+    /// Occurrences at the start of a Han run, each counted as a distinct neighbour
+    left_boundary: u64,
+    /// Occurrences at the end of a Han run, each counted as a distinct neighbour
+    right_boundary: u64,
+}
+
+fn entropy(neighbours: &FxHashMap<char, u64>, boundary: u64, total: u64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    let total = total as f64;
+    let mut h: f64 = neighbours
+        .values()
+        .map(|&c| {
+            let p = c as f64 / total;
+            -p * p.ln()
+        })
+        .sum();
+    if boundary > 0 {
+        h += boundary as f64 / total * total.ln();
+    }
+    h
+}
+
+/// Unsupervised new word discovery from a raw corpus
+///
+/// Counts the character n-grams of the Han runs of the input text and scores them by
+/// frequency, pointwise mutual information between their parts and left/right
+/// neighbour entropy. Candidates that the given [`Jieba`] dictionary already
+/// segments as one word are dropped.
+///
+/// # Examples
+///
+/// ```
+/// use jieba_rs::{Jieba, WordDiscovery, WordDiscoveryConfig};
+///
+/// let jieba = Jieba::empty();
+/// let config = WordDiscoveryConfig::builder().min_count(3).min_pmi(1.0).min_entropy(0.5).build();
+/// let mut discovery = WordDiscovery::new(config);
+/// for text in ["我爱区块链", "区块链很火", "他说区块链好", "谈区块链吧", "新的区块链呢"] {
+///     discovery.add_text(text);
+/// }
+/// let candidates = discovery.candidates(&jieba);
+/// assert_eq!(candidates[0].word, "区块链");
+///
+/// let mut dict = Vec::new();
+/// WordDiscovery::write_dict(&candidates, &mut dict).unwrap();
+/// let mut jieba = Jieba::empty();
+/// jieba.load_dict(&mut dict.as_slice()).unwrap();
+/// assert!(jieba.has_word("区块链"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct WordDiscovery {
+    config: WordDiscoveryConfig,
+    ngrams: FxHashMap<String, NgramStats>,
+    total_chars: u64,
+}
+
+impl Default for WordDiscovery {
+    fn default() -> Self {
+        WordDiscovery::new(WordDiscoveryConfig::default())
+    }
+}
+
+impl WordDiscovery {
+    /// Creates an empty WordDiscovery
+    pub fn new(config: WordDiscoveryConfig) -> Self {
+        WordDiscovery {
+            config,
+            ngrams: FxHashMap::default(),
+            total_chars: 0,
+        }
+    }
+
+    /// Gets the thresholds used to select candidate words.
+    pub fn config(&self) -> &WordDiscoveryConfig {
+        &self.config
+    }
+
+    /// Counts the n-grams of `text`.
+    ///
+    /// Text is split into runs of CJK characters; n-grams never span two runs.
+    ///
+    /// Every distinct n-gram of up to `max_word_len` chars is kept with the counts of its
+    /// neighbours, so memory grows with the size of the corpus. Call [`WordDiscovery::prune`]
+    /// between batches of text to drop the rare n-grams of a large corpus.
+    pub fn add_text(&mut self, text: &str) {
+        let mut offsets = Vec::new();
+        for state in SplitByCharacterClass::new(text, is_cjk) {
+            if state.is_matched() {
+                let run = state.as_str();
+                offsets.clear();
+                offsets.extend(run.char_indices().map(|(i, _)| i));
+                offsets.push(run.len());
+                self.add_run(run, &offsets);
+            }
+        }
+    }
+
+    /// Number of distinct n-grams counted so far
+    pub fn ngram_count(&self) -> usize {
+        self.ngrams.len()
+    }
+
+    /// Drops the n-grams of at least 2 chars seen less than `min_count` times.
+    ///
+    /// Their occurrences in text added later are counted from zero again, so candidates
+    /// whose occurrences are spread over several batches may be undercounted. Use a
+    /// `min_count` well below [`WordDiscoveryConfig::min_count`]. Single chars are always
+    /// kept, as the PMI of the candidates depends on their counts.
+    pub fn prune(&mut self, min_count: u64) {
+        self.ngrams
+            .retain(|ngram, stats| stats.count >= min_count || ngram.chars().nth(1).is_none());
+    }
+
+    fn add_run(&mut self, run: &str, offsets: &[usize]) {
+        let char_len = offsets.len() - 1;
+        self.total_chars += char_len as u64;
+        for i in 0..char_len {
+            for n in 1..=self.config.max_word_len.min(char_len - i) {
+                let key = &run[offsets[i]..offsets[i + n]];
+                let stats = match self.ngrams.get_mut(key) {
+                    Some(stats) => stats,
+                    None => self.ngrams.entry(key.to_string()).or_default(),
+                };
+                stats.count += 1;
+                if n == 1 {
+                    continue;
+                }
+                if i == 0 {
+                    stats.left_boundary += 1;
+                } else {
+                    let ch = run[offsets[i - 1]..].chars().next().unwrap();
+                    *stats.left.entry(ch).or_insert(0) += 1;
+                }
+                if i + n == char_len {
+                    stats.right_boundary += 1;
+                } else {
+                    let ch = run[offsets[i + n]..].chars().next().unwrap();
+                    *stats.right.entry(ch).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+
+    fn count(&self, ngram: &str) -> u64 {
+        self.ngrams.get(ngram).map_or(0, |s| s.count)
+    }
+
+    fn pmi(&self, word: &str, count: u64) -> f64 {
+        let total = self.total_chars as f64;
+        word.char_indices()
+            .skip(1)
+            .map(|(i, _)| {
+                let left = self.count(&word[..i]) as f64;
+                let right = self.count(&word[i..]) as f64;
+                (count as f64 * total / (left * right)).ln()
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// Returns the candidate words ranked by descending score.
+    ///
+    /// `jieba` is used to drop the candidates it already cuts as a single word and to
+    /// compute the suggested frequencies.
+    pub fn candidates(&self, jieba: &Jieba) -> Vec<WordCandidate> {
+        let mut candidates = Vec::new();
+        for (word, stats) in &self.ngrams {
+            if stats.count < self.config.min_count || word.chars().nth(1).is_none() {
+                continue;
+            }
+            let left_entropy = entropy(&stats.left, stats.left_boundary, stats.count);
+            let right_entropy = entropy(&stats.right, stats.right_boundary, stats.count);
+            if left_entropy < self.config.min_entropy || right_entropy < self.config.min_entropy {
+                continue;
+            }
+            let pmi = self.pmi(word, stats.count);
+            if pmi < self.config.min_pmi {
+                continue;
+            }
+            if jieba.cut(word, false).len() == 1 {
+                continue;
+            }
+            let freq = jieba.suggest_freq(word).max(stats.count as usize);
+            candidates.push(WordCandidate {
+                word: word.clone(),
+                count: stats.count,
+                freq,
+                pmi,
+                left_entropy,
+                right_entropy,
+                score: pmi + left_entropy.min(right_entropy),
+            });
+        }
+
+        candidates.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| b.count.cmp(&a.count))
+                .then_with(|| a.word.cmp(&b.word))
+        });
+        candidates
+    }
+
+    /// Writes `candidates` as `word freq` lines that [`Jieba::load_dict`] accepts.
+    pub fn write_dict<W: Write>(candidates: &[WordCandidate], writer: &mut W) -> io::Result<()> {
+        for candidate in candidates {
+            writeln!(writer, "{} {}", candidate.word, candidate.freq)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus() -> Vec<&'static str> {
+        vec![
+            "我爱区块链技术",
+            "区块链很火",
+            "他说区块链好",
+            "谈区块链吧，区块链。",
+            "新的区块链呢",
+            "看区块链书",
+        ]
+    }
+
+    #[test]
+    fn test_discover_words() {
+        let config = WordDiscoveryConfig::builder()
+            .min_count(3)
+            .min_pmi(1.0)
+            .min_entropy(0.5)
+            .build();
+        let mut discovery = WordDiscovery::new(config);
+        for text in corpus() {
+            discovery.add_text(text);
+        }
+
+        let candidates = discovery.candidates(&Jieba::empty());
+        let words: Vec<&str> = candidates.iter().map(|c| c.word.as_str()).collect();
+        assert_eq!(words, vec!["区块链"]);
+        assert_eq!(candidates[0].count, 7);
+        assert!(candidates[0].freq >= 7);
+    }
+
+    #[test]
+    fn test_skip_known_words() {
+        let config = WordDiscoveryConfig::builder()
+            .min_count(3)
+            .min_pmi(1.0)
+            .min_entropy(0.5)
+            .build();
+        let mut discovery = WordDiscovery::new(config);
+        for text in corpus() {
+            discovery.add_text(text);
+        }
+
+        let mut jieba = Jieba::empty();
+        jieba.add_word("区块链", Some(10), None);
+        assert!(discovery.candidates(&jieba).is_empty());
+    }
+
+    #[test]
+    fn test_prune() {
+        let config = WordDiscoveryConfig::builder()
+            .min_count(3)
+            .min_pmi(1.0)
+            .min_entropy(0.5)
+            .build();
+        let mut discovery = WordDiscovery::new(config);
+        for text in corpus() {
+            discovery.add_text(text);
+        }
+        let candidates = discovery.candidates(&Jieba::empty());
+
+        let ngrams = discovery.ngram_count();
+        discovery.prune(2);
+        assert!(discovery.ngram_count() < ngrams);
+        assert_eq!(discovery.count("我"), 1);
+        assert_eq!(discovery.count("我爱"), 0);
+        assert_eq!(discovery.candidates(&Jieba::empty()), candidates);
+    }
+
+    #[test]
+    fn test_entropy() {
+        let mut neighbours = FxHashMap::default();
+        neighbours.insert('a', 2);
+        assert_eq!(entropy(&neighbours, 0, 2), 0.0);
+        assert!((entropy(&neighbours, 2, 4) - 1.5 * 2.0f64.ln()).abs() < 1e-12);
+        assert!((entropy(&FxHashMap::default(), 2, 2) - 2.0f64.ln()).abs() < 1e-12);
+    }
+}